@@ -1,7 +1,9 @@
 use std::rc::Rc;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use slint::VecModel;
 use slint::ComponentHandle;
-use slint::Image;
+use slint::{Image, Model, Rgba8Pixel, SharedPixelBuffer, Timer, TimerMode};
 use std::thread;
 use std::time::Duration;
 use std::io::Write;
@@ -9,16 +11,37 @@ use std::path::{Path, PathBuf};
 use std::env;
 
 use jollypad_core::{shell, get_pad_items, CatacombClient, pad::IconLoader};
-use jollypad_core::game_launcher::{get_running_game, is_game_app};
+use jollypad_core::game_launcher::{close_running_game, get_running_game, is_game};
+use jollypad_core::session;
+use jollypad_core::pad::library::Library;
+use jollypad_core::pad::search::{search, SearchFilter};
+use jollypad_core::pad::tabs::{build_tabs, Tab, TabKind};
+use jollypad_core::pad::{cards, cards::CardError, cards::CardSpec, AppItem, AppSource};
+use jollypad_core::{files, files::FileFilter, i18n, thumbnails, tr};
 // use jollypad_ui::{MainWindow, PadItem};
-use std::sync::{Arc, Mutex};
+use jollypad_ui_kit::keyboard;
+use jollypad_ui_kit::text_input::{layouts_model, request_text, TextRequest};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
 // use std::time::Instant;
 
 slint::include_modules!();
 
+/// The item model of each tab, by tab id.
+type TabModels = Rc<RefCell<Vec<(String, Rc<VecModel<PadItem>>)>>>;
+/// Opens the options menu of a card.
+type ShowOptions = Rc<dyn Fn(&MainWindow, &str, Vec<CardOption>)>;
+/// The items of the grid the selection is in.
+type ShownModel = Rc<dyn Fn(&MainWindow) -> Option<Rc<VecModel<PadItem>>>>;
+/// Opens the card editor on a card, or on a new one for `None`.
+type OpenEditor = Rc<dyn Fn(Option<&str>)>;
+/// Shows a folder in the file picker.
+type ShowPickerDir = Rc<dyn Fn(&MainWindow, &Path, FileFilter)>;
+
 fn main() -> Result<(), slint::PlatformError> {
     println!("DEBUG: Jolly Home Starting...");
     std::io::stdout().flush().unwrap();
+    jollypad_ui_kit::init_translations!();
 
     let active_window = Arc::new(Mutex::new(String::new()));
     let active_class = Arc::new(Mutex::new(String::new()));
@@ -60,7 +83,20 @@ fn main() -> Result<(), slint::PlatformError> {
     run_app(active_window, active_class, icon_loader)
 }
 
-fn launch_app_helper(exec: &str, name: &str, _app_id: &str, ui_weak: slint::Weak<MainWindow>) {
+/// Number of cards in the "Recently played" tab.
+const RECENT_LIMIT: usize = 12;
+/// Pixel size card icons are decoded at, a bit above the largest card.
+const CARD_IMAGE_SIZE: u32 = 256;
+/// Pixel size of island and file picker images.
+const SMALL_IMAGE_SIZE: u32 = 64;
+/// Box art is portrait, so it needs more pixels than an icon to fill a card.
+const COVER_IMAGE_SIZE: u32 = 512;
+/// The hero or background picture covers the whole window.
+const BACKDROP_IMAGE_SIZE: u32 = 1280;
+/// app_id of the trailing "Add Card" tile in the all-apps tab.
+const ADD_CARD_ID: &str = "jollypad.add-card";
+
+fn launch_app_helper(exec: &str, name: &str, app_id: &str, ui_weak: slint::Weak<MainWindow>) {
     fn make_card_id(exec: &str, name: &str) -> String {
         use std::hash::{Hash, Hasher};
         let mut h = std::collections::hash_map::DefaultHasher::new();
@@ -77,6 +113,10 @@ fn launch_app_helper(exec: &str, name: &str, _app_id: &str, ui_weak: slint::Weak
         }
     });
     
+    if let Err(e) = Library::update(|library| library.record_launch(app_id)) {
+        eprintln!("Failed to save library: {}", e);
+    }
+
     let card_id = make_card_id(exec, name);
     shell::dispatch_exec(exec, Some(&card_id));
     
@@ -100,19 +140,225 @@ fn launch_app_helper(exec: &str, name: &str, _app_id: &str, ui_weak: slint::Weak
     });
 }
 
+/// State of the add/edit card overlay. Fields are picked from a list of options
+/// with Left/Right or the file picker; names can also be typed on the on-screen
+/// keyboard.
+#[derive(Default)]
+struct CardEditor {
+    /// app_id of the card being edited, `None` when adding one.
+    editing: Option<String>,
+    programs: Vec<CardSpec>,
+    program: usize,
+    names: Vec<String>,
+    name: usize,
+    icons: Vec<String>,
+    icon: usize,
+    is_game: bool,
+}
+
+impl CardEditor {
+    fn new_card() -> Self {
+        let mut editor = Self { programs: cards::card_suggestions(), ..Default::default() };
+        editor.select_program(0);
+        editor
+    }
+
+    fn edit(spec: CardSpec) -> Self {
+        let mut programs = vec![spec.clone()];
+        programs.extend(cards::card_suggestions().into_iter().filter(|s| s.exec != spec.exec));
+        let mut editor = Self { editing: Some(spec.app_id.clone()), programs, ..Default::default() };
+        editor.select_program(0);
+        editor
+    }
+
+    /// Pick a program and offer the names and icons that go with it.
+    fn select_program(&mut self, index: usize) {
+        self.program = index;
+        let Some(spec) = self.programs.get(index) else { return };
+        self.names = vec![spec.name.clone()];
+        // Games usually live in a folder named after them, e.g. ~/Games/Celeste/Celeste.exe
+        if let Some(dir_name) = Path::new(&spec.exec).parent().and_then(|p| p.file_name()).and_then(|n| n.to_str()) {
+            if spec.is_game && !self.names.iter().any(|n| n == dir_name) {
+                self.names.push(dir_name.to_string());
+            }
+        }
+        self.icons = Vec::new();
+        if !spec.icon.is_empty() {
+            self.icons.push(spec.icon.clone());
+        }
+        if let Some(dir) = Path::new(&spec.exec).parent().filter(|_| spec.is_game) {
+            if let Ok(entries) = std::fs::read_dir(dir) {
+                let mut images: Vec<String> = entries.flatten()
+                    .map(|e| e.path())
+                    .filter(|p| p.extension().is_some_and(|x| x == "png" || x == "svg"))
+                    .map(|p| p.to_string_lossy().into_owned())
+                    .collect();
+                images.sort();
+                self.icons.extend(images);
+            }
+        }
+        self.icons.push(String::new());
+        self.name = 0;
+        self.icon = 0;
+        self.is_game = spec.is_game;
+    }
+
+    /// Put a program chosen in the file picker first and select it.
+    fn add_program(&mut self, path: &Path) {
+        self.programs.insert(0, cards::spec_for_program(path));
+        self.select_program(0);
+    }
+
+    /// Use a name typed on the keyboard, selecting it if it was already offered.
+    fn set_name(&mut self, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+        match self.names.iter().position(|n| n == name) {
+            Some(index) => self.name = index,
+            None => {
+                self.names.insert(0, name.to_string());
+                self.name = 0;
+            }
+        }
+    }
+
+    fn add_icon(&mut self, path: &Path) {
+        self.icons.insert(0, path.to_string_lossy().into_owned());
+        self.icon = 0;
+    }
+
+    /// Folder the picker starts in for `row`: next to the current program, else ~/Games or home.
+    fn browse_start(&self, row: i32) -> PathBuf {
+        let current = if row == 2 {
+            self.icons.get(self.icon).cloned().unwrap_or_default()
+        } else {
+            self.programs.get(self.program).map(|p| p.exec.clone()).unwrap_or_default()
+        };
+        if let Some(dir) = Path::new(&current).parent().filter(|d| d.is_absolute() && d.is_dir()) {
+            return dir.to_path_buf();
+        }
+        let home = env::var("HOME").map(PathBuf::from).unwrap_or_else(|_| PathBuf::from("/"));
+        let games = home.join("Games");
+        if row == 0 && games.is_dir() { games } else { home }
+    }
+
+    fn cycle(&mut self, row: i32, delta: i32) {
+        fn step(index: usize, len: usize, delta: i32) -> usize {
+            if len == 0 { return 0; }
+            (index as i64 + delta as i64).rem_euclid(len as i64) as usize
+        }
+        match row {
+            0 => self.select_program(step(self.program, self.programs.len(), delta)),
+            1 => self.name = step(self.name, self.names.len(), delta),
+            2 => self.icon = step(self.icon, self.icons.len(), delta),
+            3 => self.is_game = !self.is_game,
+            _ => {}
+        }
+    }
+
+    fn spec(&self) -> Option<CardSpec> {
+        let program = self.programs.get(self.program)?;
+        let name = self.names.get(self.name).cloned().unwrap_or_default();
+        let app_id = self.editing.clone().unwrap_or_else(|| cards::suggest_app_id(&name));
+        Some(CardSpec {
+            app_id,
+            name,
+            exec: program.exec.clone(),
+            icon: self.icons.get(self.icon).cloned().unwrap_or_default(),
+            is_game: self.is_game,
+        })
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let spec = self.spec().ok_or_else(|| tr!("No program found to add"))?;
+        let result = match &self.editing {
+            Some(app_id) => cards::update_card(app_id, &spec),
+            None => cards::create_card(&spec),
+        };
+        result.map(|_| ()).map_err(|e| match e.downcast_ref::<CardError>() {
+            Some(e) => e.to_string(),
+            None => tr!("Could not save the card: {}", e),
+        })
+    }
+
+    fn show(&self, ui: &MainWindow, icon_loader: &IconLoader) {
+        let spec = self.spec().unwrap_or_default();
+        ui.set_editor_title(if self.editing.is_some() { tr!("Edit Card").into() } else { tr!("Add Card").into() });
+        ui.set_editor_can_delete(self.editing.is_some());
+        ui.set_editor_exec(spec.exec.into());
+        ui.set_editor_name(spec.name.into());
+        ui.set_editor_is_game(spec.is_game);
+        let icon = load_icon(icon_loader, &spec.icon);
+        ui.set_editor_has_icon(icon.is_some());
+        ui.set_editor_icon(icon.unwrap_or_default());
+        let label = Path::new(&spec.icon).file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        ui.set_editor_icon_label(if spec.icon.is_empty() { tr!("None").into() } else { label.into() });
+    }
+}
+
+/// An entry of the card options menu.
+#[derive(Debug, Clone)]
+enum CardOption {
+    Edit,
+    Favorite(bool),
+    /// Swap places with the neighbouring card of the current tab
+    Move(i32),
+    Hide,
+    ShowHidden,
+    /// Opens the list of collections to add the card to
+    AddToCollection,
+    Collection(String),
+    NewCollection,
+    RemoveFromCollection(String),
+    /// Swap the current tab with its neighbour
+    MoveTab(i32),
+}
+
+impl CardOption {
+    fn label(&self) -> String {
+        match self {
+            CardOption::Edit => tr!("Edit Card"),
+            CardOption::Favorite(true) => tr!("Add to Favorites"),
+            CardOption::Favorite(false) => tr!("Remove from Favorites"),
+            CardOption::Move(delta) if *delta < 0 => tr!("Move Left"),
+            CardOption::Move(_) => tr!("Move Right"),
+            CardOption::Hide => tr!("Hide Card"),
+            CardOption::ShowHidden => tr!("Show Hidden Cards"),
+            CardOption::AddToCollection => tr!("Add to Collection…"),
+            CardOption::Collection(name) => name.clone(),
+            CardOption::NewCollection => tr!("New Collection…"),
+            CardOption::RemoveFromCollection(name) => tr!("Remove from {}", name),
+            CardOption::MoveTab(delta) if *delta < 0 => tr!("Move Tab Left"),
+            CardOption::MoveTab(_) => tr!("Move Tab Right"),
+        }
+    }
+}
+
+/// The card order after `app_id` swaps places with its neighbour in `tab`, the
+/// card ids of the current tab. Other cards keep their place in `items`.
+fn moved_order(items: &[AppItem], tab: &[String], app_id: &str, delta: i32) -> Option<Vec<String>> {
+    let position = tab.iter().position(|id| id == app_id)?;
+    let neighbour = tab.get(position.checked_add_signed(delta as isize)?)?;
+    let mut order: Vec<String> = items.iter().map(|item| item.app_id.clone()).collect();
+    let from = order.iter().position(|id| id == app_id)?;
+    let to = order.iter().position(|id| id == neighbour)?;
+    order.swap(from, to);
+    Some(order)
+}
+
 // --------------------------------------------------------
 // Mode: Full Desktop (Monolithic)
 // --------------------------------------------------------
 fn run_app(_active_window: Arc<Mutex<String>>, _active_class: Arc<Mutex<String>>, icon_loader: Arc<IconLoader>) -> Result<(), slint::PlatformError> {
-    // Load Data
-    let pad_items_data = get_pad_items(&icon_loader);
-    println!("DEBUG: Loaded {} pad items", pad_items_data.len());
-    for item in &pad_items_data {
-        println!("DEBUG: Item '{}', icon='{}'", item.name, item.icon);
-    }
-    std::io::stdout().flush().unwrap();
-
     let ui = MainWindow::new()?;
+    jollypad_ui_kit::apply_theme!(ui, &jollypad_core::theme::current());
+    let settings = jollypad_core::settings::Settings::load();
+    jollypad_ui_kit::apply_safe_area!(ui, &settings.safe_area);
+    jollypad_ui_kit::apply_accessibility!(ui, &settings.accessibility);
+    // Pick up what jolly-settings saves while running
+    let _settings_watch = jollypad_ui_kit::follow_settings!(ui);
 
     // Register system role for Home
     CatacombClient::set_system_role("home", "^(JollyPad-Desktop|jolly-home)$");
@@ -120,35 +366,204 @@ fn run_app(_active_window: Arc<Mutex<String>>, _active_class: Arc<Mutex<String>>
     // User info
     let (user_name, user_avatar, user_initial) = get_user_info();
 
-    let pad_model_all: Rc<VecModel<PadItem>> = Rc::new(VecModel::default());
-    let pad_model_games: Rc<VecModel<PadItem>> = Rc::new(VecModel::default());
-    let pad_model_media: Rc<VecModel<PadItem>> = Rc::new(VecModel::default());
-
-    for item in &pad_items_data {
-        let icon_opt = load_icon(&icon_loader, &item.icon);
-        let pad_item = PadItem {
-            name: item.name.clone().into(),
-            icon: icon_opt.clone().unwrap_or_default(),
-            exec: item.exec.clone().into(),
-            app_id: item.app_id.clone().into(),
-            has_icon: icon_opt.is_some(),
-        };
-        
-        pad_model_all.push(pad_item.clone());
-        
-        if item.category == "Game" || is_game_app(&item.app_id).unwrap_or(false) {
-            pad_model_games.push(pad_item.clone());
-        }
-        
-        if item.category == "Media" || item.category == "Audio" || item.category == "Video" 
-           || item.app_id.contains("player") || item.app_id.contains("music") || item.app_id.contains("video") {
-             pad_model_media.push(pad_item.clone());
+    // One model per home tab; the tabs themselves follow card categories and collections
+    let tabs_model: Rc<VecModel<PadTab>> = Rc::new(VecModel::default());
+    let tab_models: TabModels = Rc::new(RefCell::new(Vec::new()));
+    ui.set_tabs(tabs_model.clone().into());
+    let pad_items_data: Rc<RefCell<Vec<AppItem>>> = Rc::new(RefCell::new(Vec::new()));
+    // Pad items by app_id, so the tabs can be rebuilt without reloading icons
+    let pad_items_by_id: Rc<RefCell<HashMap<String, PadItem>>> = Rc::new(RefCell::new(HashMap::new()));
+
+    // Favorites, recents and collections change with every launch, so the tabs are refilled afterwards
+    let refresh_tabs: Rc<dyn Fn()> = {
+        let tabs_model = tabs_model.clone();
+        let tab_models = tab_models.clone();
+        let pad_items_data = pad_items_data.clone();
+        let pad_items_by_id = pad_items_by_id.clone();
+        let ui_weak = ui.as_weak();
+        Rc::new(move || {
+            let items = pad_items_data.borrow();
+            let by_id = pad_items_by_id.borrow();
+            let tabs = build_tabs(&items, &Library::load(), RECENT_LIMIT);
+            let rows: Vec<Vec<PadItem>> = tabs
+                .iter()
+                .map(|tab| {
+                    let mut row: Vec<PadItem> =
+                        tab.items.iter().filter_map(|item| by_id.get(&item.app_id).cloned()).collect();
+                    if tab.kind == TabKind::All {
+                        row.push(PadItem {
+                            name: tr!("Add Card").into(),
+                            app_id: ADD_CARD_ID.into(),
+                            ..Default::default()
+                        });
+                    }
+                    row
+                })
+                .collect();
+
+            let mut models = tab_models.borrow_mut();
+            let ids: Vec<String> = tabs.iter().map(Tab::id).collect();
+            if models.iter().map(|(id, _)| id).eq(ids.iter()) {
+                // Same tabs: refill in place so the current tab and selection stay put
+                for ((_, model), row) in models.iter().zip(rows) {
+                    model.set_vec(row);
+                }
+                return;
+            }
+
+            let current_id = ui_weak
+                .upgrade()
+                .and_then(|ui| models.get(ui.get_current_tab() as usize).map(|(id, _)| id.clone()));
+            *models = ids.into_iter().zip(rows).map(|(id, row)| (id, Rc::new(VecModel::from(row)))).collect();
+            tabs_model.set_vec(
+                tabs.iter()
+                    .zip(models.iter())
+                    .map(|(tab, (_, model))| PadTab {
+                        title: tab_title(&tab.kind).into(),
+                        items: model.clone().into(),
+                    })
+                    .collect::<Vec<_>>(),
+            );
+            // Stay on the same tab if it still exists
+            if let Some(ui) = ui_weak.upgrade() {
+                let index = current_id.and_then(|id| models.iter().position(|(m, _)| *m == id)).unwrap_or(0) as i32;
+                if index != ui.get_current_tab() {
+                    ui.set_current_tab(index);
+                    ui.set_selected_index(0);
+                }
+            }
+        })
+    };
+
+    // Search results follow the text typed on the on-screen keyboard and the chips
+    let search_model: Rc<VecModel<PadItem>> = Rc::new(VecModel::default());
+    let search_state: Rc<RefCell<(String, SearchFilter)>> = Rc::new(RefCell::new(Default::default()));
+    ui.set_search_results(search_model.clone().into());
+    ui.set_osk_layouts(layouts_model(|name, letters, shifted| OskLayout { name, letters, shifted }));
+    ui.set_osk_layout_index(keyboard::system_layout() as i32);
+    let run_search: Rc<dyn Fn()> = {
+        let search_model = search_model.clone();
+        let search_state = search_state.clone();
+        let pad_items_data = pad_items_data.clone();
+        let pad_items_by_id = pad_items_by_id.clone();
+        let ui_weak = ui.as_weak();
+        Rc::new(move || {
+            let Some(ui) = ui_weak.upgrade() else { return };
+            let (text, filter) = &*search_state.borrow();
+            let by_id = pad_items_by_id.borrow();
+            let results = search(&pad_items_data.borrow(), &Library::load(), text, filter);
+            search_model.set_vec(
+                results.iter().filter_map(|item| by_id.get(&item.app_id).cloned()).collect::<Vec<_>>(),
+            );
+            let chips: Vec<SearchChip> = SEARCH_CHIPS
+                .iter()
+                .map(|(label, chip)| SearchChip { label: i18n::tr(label).into(), active: chip.is_active(filter) })
+                .collect();
+            ui.set_search_chips(Rc::new(VecModel::from(chips)).into());
+            ui.set_search_text(text.as_str().into());
+        })
+    };
+
+    let search_state_typed = search_state.clone();
+    let run_search_typed = run_search.clone();
+    ui.on_search_typed(move |text| {
+        search_state_typed.borrow_mut().0.push_str(&text);
+        run_search_typed();
+    });
+    let search_state_backspace = search_state.clone();
+    let run_search_backspace = run_search.clone();
+    ui.on_search_backspace(move || {
+        search_state_backspace.borrow_mut().0.pop();
+        run_search_backspace();
+    });
+    let search_state_chip = search_state.clone();
+    let run_search_chip = run_search.clone();
+    ui.on_search_toggle_chip(move |index| {
+        if let Some((_, chip)) = SEARCH_CHIPS.get(index as usize) {
+            chip.toggle(&mut search_state_chip.borrow_mut().1);
+            run_search_chip();
         }
-    }
+    });
+    let search_state_closed = search_state.clone();
+    let run_search_closed = run_search.clone();
+    ui.on_search_closed(move || {
+        *search_state_closed.borrow_mut() = Default::default();
+        run_search_closed();
+    });
+
+    // (Re)load every card, e.g. after one was added or edited. The importers and
+    // the desktop scan run on a worker thread, which then decodes the card images;
+    // a timer on the UI thread moves the results into the models as they arrive,
+    // so home shows up before they are ready
+    let reload_cards: Rc<dyn Fn()> = {
+        let (sender, receiver) = mpsc::channel::<CardUpdate>();
+        let receiver = Rc::new(receiver);
+        let generation = Arc::new(AtomicUsize::new(0));
+        let timer = Rc::new(Timer::default());
+        let pad_items_data = pad_items_data.clone();
+        let pad_items_by_id = pad_items_by_id.clone();
+        let tab_models = tab_models.clone();
+        let search_model = search_model.clone();
+        let refresh_tabs = refresh_tabs.clone();
+        let run_search = run_search.clone();
+        let icon_loader = icon_loader.clone();
+        Rc::new(move || {
+            // Results of an older reload are dropped
+            let current = generation.fetch_add(1, Ordering::SeqCst) + 1;
+            spawn_card_loader(icon_loader.clone(), current, generation.clone(), sender.clone());
+
+            let receiver = receiver.clone();
+            let pad_items_data = pad_items_data.clone();
+            let pad_items_by_id = pad_items_by_id.clone();
+            let tab_models = tab_models.clone();
+            let search_model = search_model.clone();
+            let refresh_tabs = refresh_tabs.clone();
+            let run_search = run_search.clone();
+            let timer_weak = Rc::downgrade(&timer);
+            timer.start(TimerMode::Repeated, Duration::from_millis(30), move || {
+                for update in receiver.try_iter() {
+                    match update {
+                        CardUpdate::Items(g, items) if g == current => {
+                            println!("DEBUG: Loaded {} pad items", items.len());
+                            // Icons and covers follow from the loader thread
+                            *pad_items_by_id.borrow_mut() = items
+                                .iter()
+                                .map(|item| {
+                                    (item.app_id.clone(), PadItem {
+                                        name: item.name.clone().into(),
+                                        exec: item.exec.clone().into(),
+                                        app_id: item.app_id.clone().into(),
+                                        ..Default::default()
+                                    })
+                                })
+                                .collect();
+                            *pad_items_data.borrow_mut() = items;
+                            refresh_tabs();
+                            run_search();
+                        }
+                        CardUpdate::Loaded(g, app_id, kind, pixels) if g == current => {
+                            let image = Image::from_rgba8(pixels);
+                            if let Some(item) = pad_items_by_id.borrow_mut().get_mut(&app_id) {
+                                kind.apply(item, &image);
+                            }
+                            for (_, model) in tab_models.borrow().iter() {
+                                set_model_image(model, &app_id, kind, &image);
+                            }
+                            set_model_image(&search_model, &app_id, kind, &image);
+                        }
+                        CardUpdate::Done(g) if g == current => {
+                            if let Some(timer) = timer_weak.upgrade() {
+                                timer.stop();
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            });
+        })
+    };
+    reload_cards();
 
-    ui.set_items_all(pad_model_all.into());
-    ui.set_items_games(pad_model_games.into());
-    ui.set_items_media(pad_model_media.into());
     ui.set_user_name(user_name.into());
     ui.set_has_user_avatar(user_avatar.is_some());
     if let Some(img) = user_avatar {
@@ -173,11 +588,41 @@ fn run_app(_active_window: Arc<Mutex<String>>, _active_class: Arc<Mutex<String>>
     }
     let pending_launch = Rc::new(std::cell::RefCell::new(None::<PendingLaunch>));
 
+    let editor = Rc::new(RefCell::new(CardEditor::default()));
+    let open_editor: OpenEditor = {
+        let editor = editor.clone();
+        let ui_weak = ui_weak.clone();
+        let icon_loader = icon_loader.clone();
+        Rc::new(move |app_id: Option<&str>| {
+            let Some(ui) = ui_weak.upgrade() else { return };
+            let mut editor = editor.borrow_mut();
+            match app_id {
+                Some(app_id) => match cards::load_card(app_id) {
+                    Ok(spec) => *editor = CardEditor::edit(spec),
+                    Err(e) => {
+                        eprintln!("Failed to open card {}: {}", app_id, e);
+                        return;
+                    }
+                },
+                None => *editor = CardEditor::new_card(),
+            }
+            editor.show(&ui, &icon_loader);
+            ui.set_editor_error("".into());
+            ui.set_editor_row(0);
+            ui.set_editor_action(0);
+            ui.set_editor_visible(true);
+            ui.invoke_focus_editor();
+        })
+    };
+    let open_editor_on_pad = open_editor.clone();
+
     let pending_launch_on_pad = pending_launch.clone();
     let ui_weak_on_pad = ui_weak.clone();
+    let pad_items_on_pad = pad_items_data.clone();
+    let refresh_tabs_on_pad = refresh_tabs.clone();
     ui.on_on_pad_action(move |exec_cmd: slint::SharedString, name: slint::SharedString, app_id: slint::SharedString| {
-        if name.as_str() == "Add Card" {
-            println!("TODO: Open add card dialog");
+        if app_id.as_str() == ADD_CARD_ID {
+            open_editor_on_pad(None);
         } else {
             let target_app_id = app_id.as_str();
             let clients = CatacombClient::get_clients();
@@ -192,43 +637,456 @@ fn run_app(_active_window: Arc<Mutex<String>>, _active_class: Arc<Mutex<String>>
                 CatacombClient::focus_window(&client.app_id);
                 return;
             }
+            // Still running without a matching window (launcher, headless game): don't start twice
+            if session::is_running(target_app_id) {
+                println!("Card '{}' is still running, not launching again", target_app_id);
+                return;
+            }
 
             // 2. Check if it's a game and another game is running
-            let is_game = is_game_app(target_app_id).unwrap_or(false);
-            if is_game {
-                if let Some(running_id) = get_running_game() {
-                    *pending_launch_on_pad.borrow_mut() = Some(PendingLaunch {
-                        exec: exec_cmd.to_string(),
-                        name: name.to_string(),
-                        app_id: app_id.to_string(),
-                        running_game_id: running_id.clone(),
-                    });
-                    
-                    if let Some(ui) = ui_weak_on_pad.upgrade() {
-                        ui.set_confirm_message(format!("{} is currently running. Do you want to close it and start {}?", running_id, name).into());
-                        ui.set_is_confirming(true);
-                    }
-                    return;
+            let running_game = {
+                let items = pad_items_on_pad.borrow();
+                if is_game(target_app_id, &items) { get_running_game(&items) } else { None }
+            };
+            if let Some(running) = running_game {
+                *pending_launch_on_pad.borrow_mut() = Some(PendingLaunch {
+                    exec: exec_cmd.to_string(),
+                    name: name.to_string(),
+                    app_id: app_id.to_string(),
+                    running_game_id: running.card_id,
+                });
+
+                if let Some(ui) = ui_weak_on_pad.upgrade() {
+                    ui.set_confirm_message(tr!("{} is currently running. Do you want to close it and start {}?", running.name, name).into());
+                    ui.set_is_confirming(true);
                 }
+                return;
             }
 
             // 3. Normal Launch
             launch_app_helper(exec_cmd.as_str(), name.as_str(), app_id.as_str(), ui_weak_on_pad.clone());
+            refresh_tabs_on_pad();
         }
     });
 
     let pending_launch_confirm = pending_launch.clone();
     let ui_weak_confirm = ui_weak.clone();
+    let refresh_tabs_confirm = refresh_tabs.clone();
     ui.on_on_confirm(move || {
         if let Some(launch) = pending_launch_confirm.borrow_mut().take() {
-            CatacombClient::close_window(&launch.running_game_id);
+            close_running_game(&launch.running_game_id);
             launch_app_helper(&launch.exec, &launch.name, &launch.app_id, ui_weak_confirm.clone());
+            refresh_tabs_confirm();
         }
         if let Some(ui) = ui_weak_confirm.upgrade() {
             ui.set_is_confirming(false);
         }
     });
 
+    // Card options: favorite, order, visibility and collections of the selected
+    // card, and the place of the current tab. The entries depend on the card and
+    // tab, so they are built here.
+    let card_options: Rc<RefCell<(String, Vec<CardOption>)>> = Rc::new(RefCell::new(Default::default()));
+    let show_options: ShowOptions = {
+        let card_options = card_options.clone();
+        Rc::new(move |ui: &MainWindow, app_id: &str, options: Vec<CardOption>| {
+            let labels: Vec<slint::SharedString> = options.iter().map(|option| option.label().into()).collect();
+            *card_options.borrow_mut() = (app_id.to_string(), options);
+            ui.set_options(Rc::new(VecModel::from(labels)).into());
+            ui.set_options_index(0);
+            ui.set_options_visible(true);
+        })
+    };
+    // The grid the selection is in: search results or the current tab
+    let shown_model: ShownModel = {
+        let tab_models = tab_models.clone();
+        let search_model = search_model.clone();
+        Rc::new(move |ui: &MainWindow| {
+            if ui.get_searching() {
+                return Some(search_model.clone());
+            }
+            tab_models.borrow().get(ui.get_current_tab() as usize).map(|(_, model)| model.clone())
+        })
+    };
+
+    let show_options_open = show_options.clone();
+    let ui_weak_options = ui_weak.clone();
+    let pad_items_options = pad_items_data.clone();
+    let tab_models_options = tab_models.clone();
+    ui.on_card_options(move |app_id| {
+        let Some(ui) = ui_weak_options.upgrade() else { return };
+        let library = Library::load();
+        let item = pad_items_options.borrow().iter().find(|item| item.app_id == app_id.as_str()).cloned();
+        let mut options = Vec::new();
+        if let Some(item) = &item {
+            // Only ini cards can be edited; imported ones follow their launcher
+            if item.source == AppSource::Manual {
+                options.push(CardOption::Edit);
+            }
+            options.push(CardOption::Favorite(!library.is_favorite(&item.app_id)));
+            // Search results and Recent have an order of their own
+            let in_recent = tab_models_options
+                .borrow()
+                .get(ui.get_current_tab() as usize)
+                .is_some_and(|(id, _)| *id == TabKind::Recent.id());
+            if !ui.get_searching() && !in_recent {
+                options.push(CardOption::Move(-1));
+                options.push(CardOption::Move(1));
+            }
+            options.push(CardOption::Hide);
+            options.push(CardOption::AddToCollection);
+            for collection in library.collections_of(&item.app_id) {
+                options.push(CardOption::RemoveFromCollection(collection.to_string()));
+            }
+        }
+        if library.entries.values().any(|entry| entry.hidden) {
+            options.push(CardOption::ShowHidden);
+        }
+        if !ui.get_searching() {
+            options.push(CardOption::MoveTab(-1));
+            options.push(CardOption::MoveTab(1));
+        }
+        if options.is_empty() {
+            return;
+        }
+
+        let title = item.map(|item| item.name).unwrap_or_else(|| tr!("Cards"));
+        ui.set_options_title(title.into());
+        show_options_open(&ui, app_id.as_str(), options);
+    });
+
+    let open_editor_options = open_editor.clone();
+    let ui_weak_chosen = ui_weak.clone();
+    let pad_items_chosen = pad_items_data.clone();
+    let refresh_tabs_chosen = refresh_tabs.clone();
+    let run_search_chosen = run_search.clone();
+    let reload_cards_chosen = reload_cards.clone();
+    let tab_models_chosen = tab_models.clone();
+    ui.on_option_chosen(move |index| {
+        let Some(ui) = ui_weak_chosen.upgrade() else { return };
+        let (app_id, option) = {
+            let options = card_options.borrow();
+            let Some(option) = options.1.get(index as usize) else { return };
+            (options.0.clone(), option.clone())
+        };
+        if matches!(option, CardOption::AddToCollection) {
+            let library = Library::load();
+            let joined = library.collections_of(&app_id);
+            let mut options: Vec<CardOption> = library
+                .collections
+                .keys()
+                .filter(|name| !joined.contains(&name.as_str()))
+                .map(|name| CardOption::Collection(name.clone()))
+                .collect();
+            options.push(CardOption::NewCollection);
+            show_options(&ui, app_id.as_str(), options);
+            return;
+        }
+        ui.set_options_visible(false);
+        ui.invoke_focus_pad();
+
+        // Hidden cards were left out when loading
+        let reload = matches!(option, CardOption::ShowHidden);
+        let result = match option {
+            CardOption::Edit => {
+                open_editor_options(Some(app_id.as_str()));
+                return;
+            }
+            CardOption::NewCollection => {
+                let request = TextRequest { prompt: tr!("Collection Name"), max_chars: Some(32), ..Default::default() };
+                let ui_weak = ui_weak_chosen.clone();
+                let refresh_tabs = refresh_tabs_chosen.clone();
+                let result = request_text(request, move |name| {
+                    let Some(ui) = ui_weak.upgrade() else { return };
+                    let name = name.map(|name| name.trim().to_string()).unwrap_or_default();
+                    if !name.is_empty() {
+                        match Library::update(|library| library.add_to_collection(&name, &app_id)) {
+                            Ok(()) => refresh_tabs(),
+                            Err(e) => eprintln!("Failed to save the library: {}", e),
+                        }
+                    }
+                    ui.invoke_focus_pad();
+                });
+                if let Err(e) = result {
+                    eprintln!("Failed to open the keyboard: {}", e);
+                }
+                return;
+            }
+            CardOption::Favorite(favorite) => Library::update(|library| library.set_favorite(&app_id, favorite)),
+            CardOption::Move(delta) => {
+                let Some(model) = shown_model(&ui) else { return };
+                let tab: Vec<String> = model
+                    .iter()
+                    .map(|item| item.app_id.to_string())
+                    .filter(|id| id != ADD_CARD_ID)
+                    .collect();
+                let Some(order) = moved_order(&pad_items_chosen.borrow(), &tab, &app_id, delta) else { return };
+                Library::update(|library| library.set_order(&order))
+            }
+            CardOption::Hide => Library::update(|library| library.set_hidden(&app_id, true)),
+            CardOption::ShowHidden => {
+                Library::update(|library| library.entries.values_mut().for_each(|entry| entry.hidden = false))
+            }
+            CardOption::AddToCollection => return,
+            CardOption::Collection(name) => Library::update(|library| library.add_to_collection(&name, &app_id)),
+            CardOption::RemoveFromCollection(name) => {
+                Library::update(|library| library.remove_from_collection(&name, &app_id))
+            }
+            CardOption::MoveTab(delta) => {
+                let mut ids: Vec<String> = tab_models_chosen.borrow().iter().map(|(id, _)| id.clone()).collect();
+                let current = ui.get_current_tab() as usize;
+                if current >= ids.len() {
+                    return;
+                }
+                let Some(neighbour) = current.checked_add_signed(delta as isize).filter(|i| *i < ids.len()) else {
+                    return;
+                };
+                ids.swap(current, neighbour);
+                Library::update(|library| library.set_tab_order(&ids))
+            }
+        };
+        if let Err(e) = result {
+            eprintln!("Failed to save the library: {}", e);
+            return;
+        }
+
+        if reload {
+            reload_cards_chosen();
+            return;
+        }
+        Library::load().arrange(&mut pad_items_chosen.borrow_mut());
+        refresh_tabs_chosen();
+        run_search_chosen();
+        // Keep the selection on the card, or close to where a hidden one was
+        let Some(model) = shown_model(&ui) else { return };
+        match model.iter().position(|item| item.app_id == app_id.as_str()) {
+            Some(position) => ui.set_selected_index(position as i32),
+            None => ui.set_selected_index(ui.get_selected_index().min(model.row_count() as i32 - 1).max(0)),
+        }
+    });
+
+    // Only ini cards can be edited; imported ones follow their launcher
+    let pad_items_for_edit = pad_items_data.clone();
+    ui.on_edit_card(move |app_id| {
+        let editable = pad_items_for_edit.borrow().iter()
+            .any(|item| item.app_id == app_id.as_str() && item.source == AppSource::Manual);
+        if editable {
+            open_editor(Some(app_id.as_str()));
+        }
+    });
+
+    let editor_cycle = editor.clone();
+    let ui_weak_cycle = ui_weak.clone();
+    let icon_loader_cycle = icon_loader.clone();
+    ui.on_editor_cycle(move |row, delta| {
+        let Some(ui) = ui_weak_cycle.upgrade() else { return };
+        let mut editor = editor_cycle.borrow_mut();
+        editor.cycle(row, delta);
+        editor.show(&ui, &icon_loader_cycle);
+        ui.set_editor_error("".into());
+    });
+
+    let editor_save = editor.clone();
+    let ui_weak_save = ui_weak.clone();
+    let reload_after_save = reload_cards.clone();
+    ui.on_editor_save(move || {
+        let Some(ui) = ui_weak_save.upgrade() else { return };
+        let result = editor_save.borrow().save();
+        match result {
+            Ok(()) => {
+                ui.set_editor_visible(false);
+                reload_after_save();
+                ui.invoke_focus_pad();
+            }
+            Err(e) => ui.set_editor_error(e.into()),
+        }
+    });
+
+    let editor_delete = editor.clone();
+    let ui_weak_delete = ui_weak.clone();
+    let reload_after_delete = reload_cards.clone();
+    ui.on_editor_delete(move || {
+        let Some(ui) = ui_weak_delete.upgrade() else { return };
+        let Some(app_id) = editor_delete.borrow().editing.clone() else { return };
+        match cards::delete_card(&app_id) {
+            Ok(()) => {
+                ui.set_editor_visible(false);
+                ui.set_selected_index(0);
+                reload_after_delete();
+                ui.invoke_focus_pad();
+            }
+            Err(e) => {
+                let message = match e.downcast_ref::<CardError>() {
+                    Some(e) => e.to_string(),
+                    None => tr!("Could not delete the card: {}", e),
+                };
+                ui.set_editor_error(message.into());
+            }
+        }
+    });
+
+    // Names are picked from suggestions or typed on the full screen keyboard
+    let editor_name = editor.clone();
+    let ui_weak_name = ui_weak.clone();
+    let icon_loader_name = icon_loader.clone();
+    ui.on_editor_type_name(move || {
+        let initial = {
+            let editor = editor_name.borrow();
+            editor.names.get(editor.name).cloned().unwrap_or_default()
+        };
+        let request = TextRequest { prompt: tr!("Name"), initial, max_chars: Some(64), ..Default::default() };
+        let editor_name = editor_name.clone();
+        let ui_weak_name = ui_weak_name.clone();
+        let icon_loader_name = icon_loader_name.clone();
+        let result = request_text(request, move |name| {
+            let Some(ui) = ui_weak_name.upgrade() else { return };
+            if let Some(name) = name {
+                let mut editor = editor_name.borrow_mut();
+                editor.set_name(&name);
+                editor.show(&ui, &icon_loader_name);
+            }
+            ui.invoke_focus_editor();
+        });
+        if let Err(e) = result {
+            eprintln!("Failed to open the keyboard: {}", e);
+        }
+    });
+
+    // Show a folder in the file picker. Thumbnails of its images are decoded on a
+    // worker thread and filled in as they arrive, like the card images
+    let show_picker_dir: ShowPickerDir = {
+        let (sender, receiver) = mpsc::channel::<ThumbnailUpdate>();
+        let receiver = Rc::new(receiver);
+        let generation = Arc::new(AtomicUsize::new(0));
+        let timer = Rc::new(Timer::default());
+        Rc::new(move |ui: &MainWindow, dir: &Path, filter: FileFilter| {
+            let entries = match files::list_dir(dir, filter) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return;
+                }
+            };
+            // Thumbnails of the folder that was left are dropped
+            let current = generation.fetch_add(1, Ordering::SeqCst) + 1;
+            let jobs: Vec<(usize, PathBuf)> = entries
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| entry.is_image)
+                .map(|(row, entry)| (row, entry.path.clone()))
+                .collect();
+            let model = Rc::new(VecModel::from(
+                entries
+                    .into_iter()
+                    .map(|entry| FileEntry {
+                        name: entry.name.into(),
+                        path: entry.path.to_string_lossy().into_owned().into(),
+                        is_dir: entry.is_dir,
+                        has_thumbnail: false,
+                        thumbnail: Image::default(),
+                    })
+                    .collect::<Vec<_>>(),
+            ));
+            ui.set_picker_path(dir.to_string_lossy().into_owned().into());
+            ui.set_picker_entries(model.clone().into());
+            ui.set_picker_selected(0);
+            if jobs.is_empty() {
+                return;
+            }
+            spawn_thumbnail_loader(jobs, current, generation.clone(), sender.clone());
+
+            let receiver = receiver.clone();
+            let timer_weak = Rc::downgrade(&timer);
+            timer.start(TimerMode::Repeated, Duration::from_millis(30), move || {
+                for update in receiver.try_iter() {
+                    match update {
+                        ThumbnailUpdate::Loaded(g, row, pixels) if g == current => {
+                            if let Some(mut entry) = model.row_data(row) {
+                                entry.thumbnail = Image::from_rgba8(pixels);
+                                entry.has_thumbnail = true;
+                                model.set_row_data(row, entry);
+                            }
+                        }
+                        ThumbnailUpdate::Done(g) if g == current => {
+                            if let Some(timer) = timer_weak.upgrade() {
+                                timer.stop();
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            });
+        })
+    };
+
+    // Row of the editor the file picker was opened for
+    let picker_row = Rc::new(std::cell::Cell::new(0));
+    let picker_filter = |row: i32| if row == 2 { FileFilter::Images } else { FileFilter::Programs };
+
+    let editor_browse = editor.clone();
+    let ui_weak_browse = ui_weak.clone();
+    let picker_row_browse = picker_row.clone();
+    let show_picker_dir_browse = show_picker_dir.clone();
+    ui.on_editor_browse(move |row| {
+        let Some(ui) = ui_weak_browse.upgrade() else { return };
+        picker_row_browse.set(row);
+        let bookmarks: Vec<FileEntry> = files::bookmarks().into_iter().map(|b| FileEntry {
+            name: b.label.into(),
+            path: b.path.to_string_lossy().into_owned().into(),
+            is_dir: true,
+            has_thumbnail: false,
+            thumbnail: Image::default(),
+        }).collect();
+        ui.set_picker_bookmarks(Rc::new(VecModel::from(bookmarks)).into());
+        ui.set_picker_title(if row == 2 { tr!("Choose Icon").into() } else { tr!("Choose Program").into() });
+        let start = editor_browse.borrow().browse_start(row);
+        show_picker_dir_browse(&ui, &start, picker_filter(row));
+        ui.set_picker_visible(true);
+        ui.invoke_focus_picker();
+    });
+
+    let ui_weak_picker_open = ui_weak.clone();
+    let picker_row_open = picker_row.clone();
+    ui.on_picker_open(move |path| {
+        if let Some(ui) = ui_weak_picker_open.upgrade() {
+            show_picker_dir(&ui, Path::new(path.as_str()), picker_filter(picker_row_open.get()));
+        }
+    });
+
+    let editor_pick = editor.clone();
+    let ui_weak_pick = ui_weak.clone();
+    let icon_loader_pick = icon_loader.clone();
+    let picker_row_pick = picker_row.clone();
+    ui.on_picker_pick(move |path| {
+        let Some(ui) = ui_weak_pick.upgrade() else { return };
+        let mut editor = editor_pick.borrow_mut();
+        if picker_row_pick.get() == 2 {
+            editor.add_icon(Path::new(path.as_str()));
+        } else {
+            editor.add_program(Path::new(path.as_str()));
+        }
+        editor.show(&ui, &icon_loader_pick);
+        ui.set_editor_error("".into());
+        ui.set_picker_visible(false);
+        ui.invoke_focus_editor();
+    });
+
+    let ui_weak_picker_cancel = ui_weak.clone();
+    ui.on_picker_cancel(move || {
+        if let Some(ui) = ui_weak_picker_cancel.upgrade() {
+            ui.set_picker_visible(false);
+            ui.invoke_focus_editor();
+        }
+    });
+
+    let ui_weak_close = ui_weak.clone();
+    ui.on_editor_close(move || {
+        if let Some(ui) = ui_weak_close.upgrade() {
+            ui.set_editor_visible(false);
+            ui.invoke_focus_pad();
+        }
+    });
+
     let pending_launch_cancel = pending_launch.clone();
     let ui_weak_cancel = ui_weak.clone();
     ui.on_on_cancel(move || {
@@ -274,11 +1132,52 @@ fn run_app(_active_window: Arc<Mutex<String>>, _active_class: Arc<Mutex<String>>
         }
     });
     
+    // Hero or background art of the selected card behind the grid
+    let backdrop_for = Arc::new(Mutex::new(String::new()));
+    let ui_weak_backdrop = ui.as_weak();
+    let pad_items_backdrop = pad_items_data.clone();
+    let icon_loader_backdrop = icon_loader.clone();
+    ui.on_selection_changed(move |app_id| {
+        let Some(ui) = ui_weak_backdrop.upgrade() else { return };
+        ui.set_has_backdrop(false);
+        let source = pad_items_backdrop
+            .borrow()
+            .iter()
+            .find(|item| item.app_id == app_id.as_str())
+            .map(|item| {
+                if item.artwork.background.is_empty() { item.artwork.hero.clone() } else { item.artwork.background.clone() }
+            })
+            .unwrap_or_default();
+        *backdrop_for.lock().unwrap() = app_id.to_string();
+        if source.is_empty() {
+            return;
+        }
+        let backdrop_for = backdrop_for.clone();
+        let loader = icon_loader_backdrop.clone();
+        let ui_weak = ui_weak_backdrop.clone();
+        let app_id = app_id.to_string();
+        thread::spawn(move || {
+            let Some(pixels) = decode_icon(&loader, &source, BACKDROP_IMAGE_SIZE) else { return };
+            let _ = slint::invoke_from_event_loop(move || {
+                // The selection may have moved on while decoding
+                if *backdrop_for.lock().unwrap() != app_id {
+                    return;
+                }
+                if let Some(ui) = ui_weak.upgrade() {
+                    ui.set_backdrop(Image::from_rgba8(pixels));
+                    ui.set_has_backdrop(true);
+                }
+            });
+        });
+    });
+
     // 轮询当前打开应用以更新“灵动岛”的应用图标
     let ui_weak_for_island = ui.as_weak();
     let icon_loader_for_island = icon_loader.clone();
     thread::spawn(move || {
         let mut last_ids: Vec<String> = Vec::new();
+        // Decoded once per app_id; the client list changes far more often than icons
+        let mut icon_cache: HashMap<String, Option<SharedPixelBuffer<Rgba8Pixel>>> = HashMap::new();
         
         loop {
             let clients = CatacombClient::get_clients();
@@ -319,23 +1218,30 @@ fn run_app(_active_window: Arc<Mutex<String>>, _active_class: Arc<Mutex<String>>
             if current_ids != prev_ids {
                 last_ids = current_ids.clone();
                 // 过滤系统窗口
-                let icon_loader = icon_loader_for_island.clone();
+                let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+                let windows: Vec<(jollypad_core::ClientInfo, Option<SharedPixelBuffer<Rgba8Pixel>>)> = filtered
+                    .into_iter()
+                    .filter(|client| seen.insert(client.app_id.clone()))
+                    .map(|client| {
+                        let pixels = icon_cache
+                            .entry(client.app_id.clone())
+                            .or_insert_with(|| decode_icon(&icon_loader_for_island, &client.app_id, SMALL_IMAGE_SIZE))
+                            .clone();
+                        (client, pixels)
+                    })
+                    .collect();
                 let ui_weak = ui_weak_for_island.clone();
                 let _ = slint::invoke_from_event_loop(move || {
                     if let Some(ui) = ui_weak.upgrade() {
                         let mut new_models = Vec::new();
-                        for client in filtered {
-                        let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
-                            let icon_opt = load_icon(&icon_loader, &client.app_id);
-                            if !seen.insert(client.app_id.clone()) {
-                                continue;
-                            }
+                        for (client, pixels) in windows {
                             new_models.push(PadItem {
                                 name: client.title.into(),
-                                icon: icon_opt.clone().unwrap_or_default(),
+                                has_icon: pixels.is_some(),
+                                icon: pixels.map(Image::from_rgba8).unwrap_or_default(),
                                 exec: client.app_id.clone().into(),
                                 app_id: client.app_id.into(),
-                                has_icon: icon_opt.is_some(),
+                                ..Default::default()
                             });
                         }
                         let vec_model = Rc::new(VecModel::from(new_models));
@@ -349,6 +1255,14 @@ fn run_app(_active_window: Arc<Mutex<String>>, _active_class: Arc<Mutex<String>>
     });
 
 
+    // Track game sessions so playtime is recorded even when nothing is launched from home
+    thread::spawn(|| {
+        loop {
+            session::refresh();
+            thread::sleep(Duration::from_secs(2));
+        }
+    });
+
     // 轮询手柄连接状态以更新“灵动岛”手柄数量
     let ui_weak_for_pad = ui.as_weak();
     let icon_loader_for_pad = icon_loader.clone();
@@ -368,11 +1282,9 @@ fn run_app(_active_window: Arc<Mutex<String>>, _active_class: Arc<Mutex<String>>
                         let mut icons: Vec<PadItem> = Vec::new();
                         for _ in 0..count {
                             icons.push(PadItem {
-                                name: "".into(),
                                 icon: icon_opt.clone().unwrap_or_default(),
-                                exec: "".into(),
-                                app_id: "".into(),
                                 has_icon: icon_opt.is_some(),
+                                ..Default::default()
                             });
                         }
                         let vec_model = Rc::new(VecModel::from(icons));
@@ -422,53 +1334,193 @@ fn get_user_info() -> (String, Option<Image>, String) {
 
 
 fn load_icon(loader: &IconLoader, icon_name: &str) -> Option<Image> {
-    if icon_name.is_empty() { return None; }
+    decode_icon(loader, icon_name, CARD_IMAGE_SIZE).map(Image::from_rgba8)
+}
 
-    // Handle tilde expansion
-    let clean_name = icon_name.trim().trim_matches('\u{FEFF}');
-    let expanded_name = if clean_name.starts_with("~/") {
-        if let Ok(home) = std::env::var("HOME") {
-            format!("{}/{}", home, &clean_name[2..])
-        } else {
-            clean_name.to_string()
+/// Resolve and decode an icon through the thumbnail cache. The pixels can be
+/// sent across threads, unlike `Image`.
+fn decode_icon(loader: &IconLoader, icon_name: &str, size: u32) -> Option<SharedPixelBuffer<Rgba8Pixel>> {
+    let path = loader.lookup(icon_name, size, 1)?;
+    match thumbnails::load(&path, size) {
+        Ok(thumbnail) => Some(SharedPixelBuffer::clone_from_slice(&thumbnail.pixels, thumbnail.width, thumbnail.height)),
+        Err(e) => {
+            eprintln!("Failed to load icon {:?}: {}", path, e);
+            None
         }
-    } else {
-        clean_name.to_string()
-    };
+    }
+}
 
-    std::io::stdout().flush().unwrap();
+struct CardImageJob {
+    app_id: String,
+    icon: String,
+    /// Box art path, empty for none
+    cover: String,
+}
 
-    // 1) Try absolute path directly
-    let p_check = Path::new(&expanded_name);
-    if p_check.is_absolute() || expanded_name.starts_with('/') {
-        let p = PathBuf::from(&expanded_name);
-        if p.is_file() {
-            // println!("DEBUG: Found absolute file {:?}", p);
-            match Image::load_from_path(&p) {
-                Ok(img) => return Some(img),
-                Err(_e) => {} // println!("DEBUG: Failed to load image from {:?}: {}", p, e),
+/// Which image of a card was decoded.
+#[derive(Clone, Copy)]
+enum CardImage {
+    Icon,
+    Cover,
+}
+
+impl CardImage {
+    fn apply(self, item: &mut PadItem, image: &Image) {
+        match self {
+            CardImage::Icon => {
+                item.icon = image.clone();
+                item.has_icon = true;
+            }
+            CardImage::Cover => {
+                item.cover = image.clone();
+                item.has_cover = true;
             }
         }
     }
+}
 
-            // 2) Try IconLoader best candidate
-    if let Some(p) = loader.icon_path(icon_name) {
-        let p = p.to_path_buf();
-        if p.is_file() {
-            if let Ok(img) = Image::load_from_path(&p) {
-                return Some(img);
+enum CardUpdate {
+    /// Generation and the scanned cards, without images
+    Items(usize, Vec<AppItem>),
+    /// Generation, app_id, kind and pixels of a card image
+    Loaded(usize, String, CardImage, SharedPixelBuffer<Rgba8Pixel>),
+    Done(usize),
+}
+
+/// Scan the cards, then decode their images in order, stopping early once a
+/// newer reload started. Cards with box art get the cover, others (or broken
+/// covers) the icon.
+fn spawn_card_loader(
+    loader: Arc<IconLoader>,
+    generation: usize,
+    current: Arc<AtomicUsize>,
+    sender: mpsc::Sender<CardUpdate>,
+) {
+    thread::spawn(move || {
+        let items = get_pad_items(&loader);
+        let jobs: Vec<CardImageJob> = items
+            .iter()
+            .map(|item| CardImageJob {
+                app_id: item.app_id.clone(),
+                icon: item.icon.clone(),
+                cover: item.artwork.cover.clone(),
+            })
+            .collect();
+        if sender.send(CardUpdate::Items(generation, items)).is_err() {
+            return;
+        }
+        for job in jobs {
+            if current.load(Ordering::SeqCst) != generation {
+                return;
+            }
+            let cover = if job.cover.is_empty() { None } else { decode_icon(&loader, &job.cover, COVER_IMAGE_SIZE) };
+            let image = match cover {
+                Some(pixels) => Some((CardImage::Cover, pixels)),
+                None => decode_icon(&loader, &job.icon, CARD_IMAGE_SIZE).map(|pixels| (CardImage::Icon, pixels)),
+            };
+            if let Some((kind, pixels)) = image {
+                if sender.send(CardUpdate::Loaded(generation, job.app_id, kind, pixels)).is_err() {
+                    return;
+                }
             }
         }
+        let _ = sender.send(CardUpdate::Done(generation));
+    });
+}
+
+enum ThumbnailUpdate {
+    /// Generation, file picker row and pixels of a thumbnail
+    Loaded(usize, usize, SharedPixelBuffer<Rgba8Pixel>),
+    Done(usize),
+}
+
+/// Decode file picker thumbnails in order, stopping early once another folder
+/// was opened. Files that are not images after all are left without one.
+fn spawn_thumbnail_loader(
+    jobs: Vec<(usize, PathBuf)>,
+    generation: usize,
+    current: Arc<AtomicUsize>,
+    sender: mpsc::Sender<ThumbnailUpdate>,
+) {
+    thread::spawn(move || {
+        for (row, path) in jobs {
+            if current.load(Ordering::SeqCst) != generation {
+                return;
+            }
+            let Ok(thumbnail) = thumbnails::load(&path, SMALL_IMAGE_SIZE) else { continue };
+            let pixels = SharedPixelBuffer::clone_from_slice(&thumbnail.pixels, thumbnail.width, thumbnail.height);
+            if sender.send(ThumbnailUpdate::Loaded(generation, row, pixels)).is_err() {
+                return;
+            }
+        }
+        let _ = sender.send(ThumbnailUpdate::Done(generation));
+    });
+}
+
+/// A filter chip of the home search.
+#[derive(Clone, Copy)]
+enum SearchChipKind {
+    Favorites,
+    Recent,
+    Source(AppSource),
+}
+
+impl SearchChipKind {
+    fn is_active(self, filter: &SearchFilter) -> bool {
+        match self {
+            SearchChipKind::Favorites => filter.favorites,
+            SearchChipKind::Recent => filter.recent,
+            SearchChipKind::Source(source) => filter.source == Some(source),
+        }
     }
-    // 3) Fallback: prefer PNG/XPM in known dirs
-    if let Some(p) = resolve_icon_path(icon_name) {
-        if p.is_file() {
-            if let Ok(img) = Image::load_from_path(&p) {
-                return Some(img);
+
+    /// Source chips are exclusive: turning one on turns the others off.
+    fn toggle(self, filter: &mut SearchFilter) {
+        match self {
+            SearchChipKind::Favorites => filter.favorites = !filter.favorites,
+            SearchChipKind::Recent => filter.recent = !filter.recent,
+            SearchChipKind::Source(source) => {
+                filter.source = if filter.source == Some(source) { None } else { Some(source) };
             }
         }
     }
-    None
+}
+
+// Labels are msgids, translated when the chips are shown
+const SEARCH_CHIPS: [(&str, SearchChipKind); 7] = [
+    ("Favorites", SearchChipKind::Favorites),
+    ("Recent", SearchChipKind::Recent),
+    ("Steam", SearchChipKind::Source(AppSource::Steam)),
+    ("Heroic", SearchChipKind::Source(AppSource::Heroic)),
+    ("Lutris", SearchChipKind::Source(AppSource::Lutris)),
+    ("Cards", SearchChipKind::Source(AppSource::Manual)),
+    ("Apps", SearchChipKind::Source(AppSource::Desktop)),
+];
+
+/// Label of a home tab. The categories desktop entries are sorted into get
+/// translated; user categories and collections show as written.
+fn tab_title(kind: &TabKind) -> String {
+    match kind {
+        TabKind::All => tr!("All Apps"),
+        TabKind::Favorites => tr!("Favorites"),
+        TabKind::Recent => tr!("Recent"),
+        TabKind::Category(name) => match name.to_lowercase().as_str() {
+            "game" => tr!("Games"),
+            "media" => tr!("Media"),
+            _ => name.clone(),
+        },
+        TabKind::Collection(name) => name.clone(),
+    }
+}
+
+fn set_model_image(model: &VecModel<PadItem>, app_id: &str, kind: CardImage, image: &Image) {
+    for row in 0..model.row_count() {
+        let Some(mut item) = model.row_data(row) else { continue };
+        if item.app_id == app_id {
+            kind.apply(&mut item, image);
+            model.set_row_data(row, item);
+        }
+    }
 }
 
 fn load_controller_icon(loader: &IconLoader) -> Option<Image> {
@@ -496,67 +1548,6 @@ fn preload_runtime() {
     // let _ = Command::new("sh").arg("-c").arg(cmd).spawn();
 }
 
-fn resolve_icon_path(icon_name: &str) -> Option<std::path::PathBuf> {
-    if icon_name.is_empty() { return None; }
-    let p = std::path::PathBuf::from(icon_name);
-    if p.is_absolute() && p.is_file() {
-        return Some(p);
-    }
-
-    // Build search paths dynamically
-    let mut dirs: Vec<&'static str> = Vec::new();
-
-    // 1. Check environment variable JOLLYPAD_ICON_DIR
-    if let Ok(icon_dir) = std::env::var("JOLLYPAD_ICON_DIR") {
-        dirs.push(Box::leak(icon_dir.into_boxed_str()));
-    }
-
-    // 2. Check relative to executable
-    if let Ok(exe_path) = std::env::current_exe() {
-        if let Some(exe_dir) = exe_path.parent() {
-            // Try ../share/jollypad/icons (relative to binary)
-            dirs.push(Box::leak(exe_dir.join("../share/jollypad/icons").to_string_lossy().into_owned().into_boxed_str()));
-            // Try ./icons (same dir as binary)
-            dirs.push(Box::leak(exe_dir.join("icons").to_string_lossy().into_owned().into_boxed_str()));
-        }
-    }
-
-    // 3. Check current working directory (for dev)
-    if let Ok(cwd) = std::env::current_dir() {
-        dirs.push(Box::leak(cwd.join("assets/icons").to_string_lossy().into_owned().into_boxed_str()));
-    }
-
-    // 4. Standard system paths
-    dirs.extend([
-        "/usr/share/jollypad/icons",
-        "/usr/share/pixmaps",
-        "/usr/share/icons/hicolor/512x512/apps",
-        "/usr/share/icons/hicolor/256x256/apps",
-        "/usr/share/icons/hicolor/128x128/apps",
-        "/usr/share/icons/hicolor/48x48/apps",
-        "/usr/share/icons/hicolor/scalable/apps",
-        "/usr/share/icons/Adwaita/48x48/apps",
-        "/usr/share/icons/Adwaita/scalable/apps",
-    ]);
-    
-    let extensions = ["png", "svg", "xpm"];
-    
-    for dir in dirs {
-        for ext in extensions {
-            let candidate = std::path::Path::new(dir).join(format!("{}.{}", icon_name, ext));
-            if candidate.is_file() {
-                return Some(candidate);
-            }
-        }
-        // Try without extension
-        let candidate = std::path::Path::new(dir).join(icon_name);
-        if candidate.is_file() {
-            return Some(candidate);
-        }
-    }
-    None
-}
-
 fn count_gamepads() -> usize {
     use std::fs;
     // 优先使用 js* 设备作为“手柄”计数，避免与 event-joystick 同时计数造成重复
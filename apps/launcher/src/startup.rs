@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use catacomb_ipc::{IpcMessage, KeyTrigger, Keysyms};
+use jollypad_core::settings::Settings;
 use std::fs;
 use std::process::{Command, Stdio};
 use std::str::FromStr;
@@ -21,10 +22,13 @@ pub fn run() -> Result<()> {
     // 3. Settings
     setup_settings()?;
 
-    // 4. Audio Setup
+    // 4. Display, scale, app scales, orientation and keyboard from jolly-settings
+    restore_settings();
+
+    // 5. Audio Setup
     setup_audio()?;
 
-    // 5. Spawn background apps
+    // 6. Spawn background apps
     spawn_apps()?;
 
     println!("✅ Jolly Startup completed.");
@@ -195,7 +199,8 @@ fn setup_key_bindings() -> Result<()> {
         ],
     })?;
 
-    // Virtual Keyboard
+    // Virtual Keyboard: squeekboard, for other apps. JollyPad's own windows
+    // bring the gamepad keyboard from jollypad-ui-kit instead.
     send(IpcMessage::BindKey {
         app_id: "*".to_string(),
         mods: None,
@@ -260,6 +265,16 @@ fn setup_settings() -> Result<()> {
     Ok(())
 }
 
+fn restore_settings() {
+    println!("🖥️ Restoring saved settings...");
+    for msg in Settings::load().compositor_messages() {
+        // One setting the compositor rejects should not cost the others
+        if let Err(e) = send(msg) {
+            eprintln!("⚠️ Failed to restore setting: {}", e);
+        }
+    }
+}
+
 fn spawn_apps() -> Result<()> {
     // squeekboard
     // Command::new("squeekboard").spawn()?;
@@ -4,13 +4,15 @@ use slint::VecModel;
 use slint::ComponentHandle;
 use slint::Image;
 use std::thread;
+use std::sync::OnceLock;
 use std::time::{Duration, Instant};
 
 use jollypad_core::shell;
 use jollypad_core::clients;
+use jollypad_core::pad::IconLoader;
 // use jollypad_ui::{NavOverlay, PadItem};
 
-use jollypad_core::CatacombClient;
+use jollypad_core::{tr, CatacombClient};
 
 slint::include_modules!();
 
@@ -21,75 +23,35 @@ fn main() -> Result<(), slint::PlatformError> {
     std::env::set_var("WAYLAND_APP_ID", "jolly-nav");
     std::env::set_var("WINIT_APP_ID", "jolly-nav");
     std::env::set_var("APP_ID", "jolly-nav");
+    jollypad_ui_kit::init_translations!();
     let ui = NavOverlay::new()?;
+    jollypad_ui_kit::apply_theme!(ui, &jollypad_core::theme::current());
+    let settings = jollypad_core::settings::Settings::load();
+    jollypad_ui_kit::apply_safe_area!(ui, &settings.safe_area);
+    jollypad_ui_kit::apply_accessibility!(ui, &settings.accessibility);
 
     // Register system role for Nav overlay (app_id preferred)
     CatacombClient::set_system_role("nav", "^jolly-nav$");
     CatacombClient::set_system_role("overlay", "^jolly-nav$");
 
     // Navbar Items
-    let nav_model: Rc<VecModel<PadItem>> = Rc::new(VecModel::default());
-    let nav_items_list = vec![
-        ("主页", "home"),
-        ("概览", "view-app-grid"),
-        ("设置", "cog-outline"),
-        ("手柄", "controller"),
-        ("用户", "account"),
-        ("关机", "power"),
-    ];
-    
-    // User info for avatar
-    let (_, user_avatar, _) = get_user_info();
-    
-    for (name, icon_name) in nav_items_list {
-        let mut icon = load_icon(icon_name).unwrap_or_default();
-        let mut has_icon = load_icon(icon_name).is_some();
-        
-        // Fallback for overview icon
-        if name == "概览" && !has_icon {
-             icon = load_icon("dock-window").unwrap_or_default();
-             has_icon = load_icon("dock-window").is_some();
-        }
-
-        if name == "用户" && user_avatar.is_some() {
-             icon = user_avatar.clone().unwrap();
-             has_icon = true;
-        }
-        
-        nav_model.push(PadItem {
-            name: name.into(),
-            icon: icon,
-            exec: "".into(),
-            app_id: "".into(),
-            has_icon: has_icon,
-        });
-    }
-    ui.set_nav_items(nav_model.into());
+    let nav_model: Rc<VecModel<PadItem>> = Rc::new(VecModel::from(nav_items()));
+    ui.set_nav_items(nav_model.clone().into());
     
     // Window Items
     let windows_model: Rc<VecModel<PadItem>> = Rc::new(VecModel::default());
     ui.set_window_items(windows_model.clone().into());
 
     // Power Items
-    let power_model: Rc<VecModel<PadItem>> = Rc::new(VecModel::default());
-    let power_items_list = vec![
-        ("关机", "power", "poweroff"),
-        ("重启", "restart", "reboot"),
-        ("挂起", "power-sleep", "systemctl suspend"),
-        ("注销", "logout", "wlogout"), 
-        ("锁定", "monitor-lock", "loginctl lock-session"),
-    ];
-    for (name, icon_name, exec) in power_items_list {
-        let icon = load_icon(icon_name).unwrap_or_else(|| load_icon("application-default-icon").unwrap_or_default());
-        power_model.push(PadItem {
-            name: name.into(),
-            icon: icon,
-            exec: exec.into(),
-            app_id: "".into(),
-            has_icon: true,
-        });
-    }
-    ui.set_power_items(power_model.into());
+    let power_model: Rc<VecModel<PadItem>> = Rc::new(VecModel::from(power_items()));
+    ui.set_power_items(power_model.clone().into());
+
+    // Pick up what jolly-settings saves while running; the labels are built
+    // here with tr!(), so a new language needs them built again
+    let _settings_watch = jollypad_ui_kit::follow_settings!(ui, move || {
+        nav_model.set_vec(nav_items());
+        power_model.set_vec(power_items());
+    });
 
     // Register SIGUSR1 handler for external toggle
     use std::sync::atomic::{AtomicBool, Ordering};
@@ -311,7 +273,8 @@ fn main() -> Result<(), slint::PlatformError> {
                              icon,
                              exec: c.address.into(),
                              app_id: "".into(),
-                             has_icon: true, 
+                             has_icon: true,
+                             ..Default::default()
                          });
                     }
                     windows_model.set_vec(items);
@@ -469,78 +432,79 @@ fn main() -> Result<(), slint::PlatformError> {
     ui.run()
 }
 
-// Reuse icon loading logic
-fn load_icon(icon_name: &str) -> Option<Image> {
-    use std::path::PathBuf;
+/// The nav bar entries, labelled in the current language.
+fn nav_items() -> Vec<PadItem> {
+    // The id tells nav.slint which items expand into a content row
+    let nav_items_list = vec![
+        (tr!("Home"), "home", "home"),
+        (tr!("Overview"), "view-app-grid", "overview"),
+        (tr!("Settings"), "cog-outline", "settings"),
+        (tr!("Controller"), "controller", "controller"),
+        (tr!("User"), "account", "user"),
+        (tr!("Power"), "power", "power"),
+    ];
     
-    fn resolve_icon_path(icon_name: &str) -> Option<PathBuf> {
-        if icon_name.is_empty() { return None; }
-        if std::path::Path::new(icon_name).is_file() {
-            return Some(PathBuf::from(icon_name));
-        }
-
-        // Build search paths dynamically
-        let mut search_paths: Vec<String> = Vec::new();
-
-        // 1. Check environment variable JOLLYPAD_ICON_DIR
-        if let Ok(icon_dir) = std::env::var("JOLLYPAD_ICON_DIR") {
-            search_paths.push(icon_dir);
-        }
-
-        // 2. Check relative to executable
-        if let Ok(exe_path) = std::env::current_exe() {
-            if let Some(exe_dir) = exe_path.parent() {
-                // Try ../share/jollypad/icons (relative to binary)
-                search_paths.push(exe_dir.join("../share/jollypad/icons").to_string_lossy().into_owned());
-                // Try ./icons (same dir as binary)
-                search_paths.push(exe_dir.join("icons").to_string_lossy().into_owned());
-            }
-        }
-
-        // 3. Check current working directory (for dev)
-        if let Ok(cwd) = std::env::current_dir() {
-            search_paths.push(cwd.join("assets/icons").to_string_lossy().into_owned());
+    // User info for avatar
+    let (_, user_avatar, _) = get_user_info();
+    
+    let mut items = Vec::new();
+    for (name, icon_name, id) in nav_items_list {
+        let mut icon = load_icon(icon_name).unwrap_or_default();
+        let mut has_icon = load_icon(icon_name).is_some();
+        
+        // Fallback for overview icon
+        if id == "overview" && !has_icon {
+             icon = load_icon("dock-window").unwrap_or_default();
+             has_icon = load_icon("dock-window").is_some();
         }
 
-        // 4. Standard system paths
-        search_paths.extend(vec![
-            "/usr/share/jollypad/icons".to_string(),
-            "/usr/share/icons/hicolor/512x512/apps".to_string(),
-            "/usr/share/icons/hicolor/256x256/apps".to_string(),
-            "/usr/share/icons/hicolor/128x128/apps".to_string(),
-            "/usr/share/icons/hicolor/64x64/apps".to_string(),
-            "/usr/share/icons/Adwaita/64x64/places".to_string(),
-            "/usr/share/icons/Adwaita/64x64/apps".to_string(),
-            "/usr/share/pixmaps".to_string(),
-        ]);
-
-        let extensions = ["png", "svg", "xpm"];
-
-        for path in &search_paths {
-            for ext in &extensions {
-                let p = PathBuf::from(format!("{}/{}.{}", path, icon_name, ext));
-                if p.is_file() {
-                    return Some(p);
-                }
-            }
-            // Try without extension
-            let p = PathBuf::from(format!("{}/{}", path, icon_name));
-            if p.is_file() {
-                return Some(p);
-            }
+        if id == "user" && user_avatar.is_some() {
+             icon = user_avatar.clone().unwrap();
+             has_icon = true;
         }
-        None
+        
+        items.push(PadItem {
+            name: name.into(),
+            icon,
+            exec: "".into(),
+            app_id: id.into(),
+            has_icon,
+            ..Default::default()
+        });
     }
+    items
+}
 
-    let path = resolve_icon_path(icon_name);
-    if let Some(p) = path {
-        if p.is_file() {
-            if let Ok(img) = Image::load_from_path(&p) {
-                return Some(img);
+/// The power menu entries, labelled in the current language.
+fn power_items() -> Vec<PadItem> {
+    let power_items_list = vec![
+        (tr!("Shut Down"), "power", "poweroff"),
+        (tr!("Restart"), "restart", "reboot"),
+        (tr!("Suspend"), "power-sleep", "systemctl suspend"),
+        (tr!("Log Out"), "logout", "wlogout"), 
+        (tr!("Lock"), "monitor-lock", "loginctl lock-session"),
+    ];
+    power_items_list
+        .into_iter()
+        .map(|(name, icon_name, exec)| {
+            let icon = load_icon(icon_name).unwrap_or_else(|| load_icon("application-default-icon").unwrap_or_default());
+            PadItem {
+                name: name.into(),
+                icon,
+                exec: exec.into(),
+                app_id: "".into(),
+                has_icon: true,
+                ..Default::default()
             }
-        }
-    }
-    None
+        })
+        .collect()
+}
+
+// Same icon lookup as home; the index is built once per process and cached on disk
+fn load_icon(icon_name: &str) -> Option<Image> {
+    static ICONS: OnceLock<IconLoader> = OnceLock::new();
+    let path = ICONS.get_or_init(IconLoader::new).lookup(icon_name, 64, 1)?;
+    Image::load_from_path(&path).ok()
 }
 
 fn make_nav_card_id(exec: &str) -> String {
@@ -1,5 +1,6 @@
 fn main() {
     let config = slint_build::CompilerConfiguration::new()
-        .with_include_paths(vec![std::path::PathBuf::from("../../crates/ui-kit/ui")]);
+        .with_include_paths(vec![std::path::PathBuf::from("../../crates/ui-kit/ui")])
+        .with_bundled_translations("../../translations");
     slint_build::compile_with_config("ui/settings.slint", config).unwrap();
 }
@@ -1,13 +1,26 @@
-use slint::{ComponentHandle, VecModel, Timer};
+use slint::{ComponentHandle, Model, VecModel, Timer};
 use std::rc::Rc;
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
 use jollypad_core::CatacombClient;
-use catacomb_ipc::{IpcMessage, WindowScale};
+use jollypad_core::pad::{cards, IconLoader};
+use jollypad_core::settings::{self as config, DisplayMode, SafeArea, ScaleRule, Settings};
+use jollypad_core::xkb::{XkbItem, XkbRules};
+use jollypad_core::{i18n, theme, tr};
+use catacomb_ipc::{IpcMessage, Orientation, WindowScale};
 
 slint::include_modules!();
 
 fn main() -> Result<(), slint::PlatformError> {
+    jollypad_ui_kit::init_translations!();
     let ui = SettingsWindow::new()?;
+    let current_theme = theme::current();
+    jollypad_ui_kit::apply_theme!(ui, &current_theme);
+    let settings = Settings::load();
+    jollypad_ui_kit::apply_safe_area!(ui, &settings.safe_area);
+    jollypad_ui_kit::apply_accessibility!(ui, &settings.accessibility);
+    // Stay in step with the file, e.g. when it is edited by hand
+    let _settings_watch = jollypad_ui_kit::follow_settings!(ui);
     
     // Set role for window manager if needed
     CatacombClient::set_system_role("settings", "JollyPad-Settings");
@@ -46,89 +59,99 @@ fn main() -> Result<(), slint::PlatformError> {
          ui.set_current_resolution(format!("{pw}×{ph}").into());
     }
 
-    // Confirmation State
-    let confirmed_mode_index = Rc::new(Cell::new(current_mode_index as usize));
+    // Resolution and orientation changes are tried out first: the confirmation
+    // popup keeps them, going back or letting the countdown run out reverts them
+    let trial: Rc<RefCell<Option<Trial>>> = Rc::default();
     let timer = Rc::new(Timer::default());
+    let finish_trial = {
+        let trial = trial.clone();
+        let timer = timer.clone();
+        let ui_weak = ui.as_weak();
+        Rc::new(move |keep: bool| {
+            timer.stop();
+            let pending = trial.borrow_mut().take();
+            if let Some(pending) = pending {
+                if keep { (pending.keep)() } else { (pending.revert)() }
+            }
+            if let Some(ui) = ui_weak.upgrade() {
+                ui.set_show_confirmation_popup(false);
+            }
+        })
+    };
+    let start_trial = {
+        let trial = trial.clone();
+        let timer = timer.clone();
+        let finish_trial = finish_trial.clone();
+        let ui_weak = ui.as_weak();
+        Rc::new(move |pending: Trial| {
+            // A change still on trial is reverted by the new one anyway
+            trial.replace(Some(pending));
+            let Some(ui) = ui_weak.upgrade() else { return };
+            ui.set_countdown_seconds(15);
+            ui.set_show_confirmation_popup(true);
 
-    // Apply Resolution
+            let ui_weak = ui_weak.clone();
+            let finish_trial = finish_trial.clone();
+            timer.start(slint::TimerMode::Repeated, std::time::Duration::from_secs(1), move || {
+                let Some(ui) = ui_weak.upgrade() else { return };
+                let s = ui.get_countdown_seconds() - 1;
+                ui.set_countdown_seconds(s);
+                if s <= 0 {
+                    finish_trial(false);
+                }
+            });
+        })
+    };
+    ui.on_confirm_display({
+        let finish_trial = finish_trial.clone();
+        move || finish_trial(true)
+    });
+    ui.on_revert_display(move || finish_trial(false));
+
+    // Apply Resolution: kept for the next session too once confirmed
     {
         let modes = modes.clone();
         let ui_weak = ui.as_weak();
-        let confirmed_idx = confirmed_mode_index.clone();
-        let timer = timer.clone();
-        
+        let confirmed_idx = Rc::new(Cell::new(current_mode_index as usize));
+        let start_trial = start_trial.clone();
+
         ui.on_apply_resolution(move |idx| {
             let idx = idx.clamp(0, (modes.len() - 1) as i32) as usize;
             let mode = &modes[idx];
-            
+
             // Apply immediately
             let _ = CatacombClient::send_message(IpcMessage::SetOutputMode { mode: mode.clone() });
-            
             if let Some(ui) = ui_weak.upgrade() {
-                 ui.set_current_resolution(format!("{}x{} @ {:.2}Hz", mode.width, mode.height, mode.refresh as f64 / 1000.0).into());
-                 ui.set_countdown_seconds(15);
-                 ui.set_show_confirmation_popup(true);
+                ui.set_current_resolution(format!("{}x{} @ {:.2}Hz", mode.width, mode.height, mode.refresh as f64 / 1000.0).into());
             }
 
-            let ui_weak_timer = ui_weak.clone();
-            let timer_handle = timer.clone();
-            let modes_timer = modes.clone();
-            let confirmed_idx_timer = confirmed_idx.clone();
-
-            timer.start(slint::TimerMode::Repeated, std::time::Duration::from_secs(1), move || {
-                if let Some(ui) = ui_weak_timer.upgrade() {
-                    let mut s = ui.get_countdown_seconds();
-                    s -= 1;
-                    ui.set_countdown_seconds(s);
-                    
-                    if s <= 0 {
-                        // Revert
-                        let idx = confirmed_idx_timer.get();
-                        let mode = &modes_timer[idx];
-                        let _ = CatacombClient::send_message(IpcMessage::SetOutputMode { mode: mode.clone() });
+            let keep = {
+                let confirmed_idx = confirmed_idx.clone();
+                let mode = DisplayMode::from(mode);
+                move || {
+                    confirmed_idx.set(idx);
+                    let result = Settings::update(|settings| {
+                        settings.display.mode = Some(mode);
+                    });
+                    if let Err(e) = result {
+                        eprintln!("Failed to save resolution: {:#}", e);
+                    }
+                }
+            };
+            let revert = {
+                let previous = confirmed_idx.get();
+                let modes = modes.clone();
+                let ui_weak = ui_weak.clone();
+                move || {
+                    let mode = &modes[previous];
+                    let _ = CatacombClient::send_message(IpcMessage::SetOutputMode { mode: mode.clone() });
+                    if let Some(ui) = ui_weak.upgrade() {
                         ui.set_current_resolution(format!("{}x{} @ {:.2}Hz", mode.width, mode.height, mode.refresh as f64 / 1000.0).into());
-                        ui.set_show_confirmation_popup(false);
-                        ui.set_resolution_popup_index(idx as i32);
-                        timer_handle.stop();
+                        ui.set_resolution_popup_index(previous as i32);
                     }
                 }
-            });
-        });
-    }
-
-    // Confirm Resolution
-    {
-        let confirmed_idx = confirmed_mode_index.clone();
-        let timer = timer.clone();
-        let ui_weak = ui.as_weak();
-        
-        ui.on_confirm_resolution(move || {
-            timer.stop();
-            if let Some(ui) = ui_weak.upgrade() {
-                ui.set_show_confirmation_popup(false);
-                let current_idx = ui.get_resolution_popup_index();
-                confirmed_idx.set(current_idx as usize);
-            }
-        });
-    }
-
-    // Revert Resolution
-    {
-        let modes = modes.clone();
-        let confirmed_idx = confirmed_mode_index.clone();
-        let timer = timer.clone();
-        let ui_weak = ui.as_weak();
-        
-        ui.on_revert_resolution(move || {
-            timer.stop();
-            if let Some(ui) = ui_weak.upgrade() {
-                let idx = confirmed_idx.get();
-                let mode = &modes[idx];
-                let _ = CatacombClient::send_message(IpcMessage::SetOutputMode { mode: mode.clone() });
-                ui.set_current_resolution(format!("{}x{} @ {:.2}Hz", mode.width, mode.height, mode.refresh as f64 / 1000.0).into());
-                ui.set_show_confirmation_popup(false);
-                ui.set_resolution_popup_index(idx as i32);
-            }
+            };
+            start_trial(Trial { keep: Box::new(keep), revert: Box::new(revert) });
         });
     }
 
@@ -157,6 +180,12 @@ fn main() -> Result<(), slint::PlatformError> {
             let idx = idx.clamp(0, (scales.len() - 1) as i32) as usize;
             let scale = scales[idx];
             let _ = CatacombClient::send_message(IpcMessage::Scale { scale: WindowScale::Fixed(scale), app_id: None });
+            let result = Settings::update(|settings| {
+                settings.display.scale = Some(scale);
+            });
+            if let Err(e) = result {
+                eprintln!("Failed to save scale: {:#}", e);
+            }
             
             if let Some(ui) = ui_weak.upgrade() {
                 ui.set_current_scale(format!("{:.0}%", scale * 100.0).into());
@@ -164,6 +193,355 @@ fn main() -> Result<(), slint::PlatformError> {
         });
     }
     
+    // Orientation and keyboard settings are picked from choice lists
+    let confirmed_orientation = Rc::new(Cell::new(settings.display.orientation));
+    ui.set_current_orientation(orientation_name(settings.display.orientation).into());
+    let rules = Rc::new(XkbRules::load());
+    let keyboard = Rc::new(RefCell::new(settings.keyboard));
+    ui.set_keyboard_values(Rc::new(VecModel::from(keyboard_values(&rules, &keyboard.borrow()))).into());
+    ui.on_open_choice({
+        let ui_weak = ui.as_weak();
+        let rules = rules.clone();
+        let keyboard = keyboard.clone();
+        let confirmed_orientation = confirmed_orientation.clone();
+        move |kind| {
+            let Some(ui) = ui_weak.upgrade() else { return };
+            let keyboard = keyboard.borrow();
+            let default = || slint::SharedString::from(tr!("System Default"));
+            let (title, options, index): (String, Vec<slint::SharedString>, Option<usize>) = match kind.as_str() {
+                "orientation" => (
+                    tr!("Orientation"),
+                    ORIENTATIONS.iter().map(|&orientation| orientation_name(orientation).into()).collect(),
+                    ORIENTATIONS.iter().position(|&orientation| orientation == confirmed_orientation.get()),
+                ),
+                "keyboard-model" => (
+                    tr!("Keyboard Model"),
+                    std::iter::once(default()).chain(rules.models.iter().map(|m| m.description.as_str().into())).collect(),
+                    rules.models.iter().position(|m| m.name == keyboard.model).map(|i| i + 1),
+                ),
+                "keyboard-layout" => (
+                    tr!("Keyboard Layout"),
+                    std::iter::once(default()).chain(rules.layouts.iter().map(|l| l.description.as_str().into())).collect(),
+                    rules.layouts.iter().position(|l| l.name == primary(&keyboard.layout)).map(|i| i + 1),
+                ),
+                "keyboard-variant" => (
+                    tr!("Keyboard Variant"),
+                    std::iter::once(default())
+                        .chain(rules.variants_of(primary(&keyboard.layout)).map(|v| v.description.as_str().into()))
+                        .collect(),
+                    rules.variants_of(primary(&keyboard.layout)).position(|v| v.name == primary(&keyboard.variant)).map(|i| i + 1),
+                ),
+                "keyboard-options" => (
+                    tr!("Keyboard Options"),
+                    rules.options.iter().map(|o| o.description.as_str().into()).collect(),
+                    None,
+                ),
+                _ => return,
+            };
+            let checked: Vec<bool> = match kind.as_str() {
+                "keyboard-options" => rules.options.iter().map(|o| keyboard.options.split(',').any(|name| name == o.name)).collect(),
+                _ => Vec::new(),
+            };
+            ui.set_choice_title(title.into());
+            ui.set_choice_options(Rc::new(VecModel::from(options)).into());
+            ui.set_choice_checked(Rc::new(VecModel::from(checked)).into());
+            ui.set_choice_multiple(kind == "keyboard-options");
+            ui.set_choice_index(index.unwrap_or(0) as i32);
+        }
+    });
+    ui.on_choose({
+        let ui_weak = ui.as_weak();
+        move |kind, idx| {
+            let Some(ui) = ui_weak.upgrade() else { return };
+            let idx = idx.max(0) as usize;
+            if kind == "orientation" {
+                let Some(&orientation) = ORIENTATIONS.get(idx) else { return };
+                lock_orientation(orientation);
+                ui.set_current_orientation(orientation_name(orientation).into());
+                let keep = {
+                    let confirmed_orientation = confirmed_orientation.clone();
+                    move || {
+                        confirmed_orientation.set(orientation);
+                        let result = Settings::update(|settings| {
+                            settings.display.orientation = orientation;
+                        });
+                        if let Err(e) = result {
+                            eprintln!("Failed to save orientation: {:#}", e);
+                        }
+                    }
+                };
+                let revert = {
+                    let previous = confirmed_orientation.get();
+                    let ui_weak = ui_weak.clone();
+                    move || {
+                        lock_orientation(previous);
+                        if let Some(ui) = ui_weak.upgrade() {
+                            ui.set_current_orientation(orientation_name(previous).into());
+                        }
+                    }
+                };
+                start_trial(Trial { keep: Box::new(keep), revert: Box::new(revert) });
+                return;
+            }
+
+            // Keyboard changes apply right away; a wrong layout is undone from the same list
+            let mut keyboard = keyboard.borrow_mut();
+            // Index 0 is the system default in all lists but the options
+            let name = |names: &mut dyn Iterator<Item = &str>| idx.checked_sub(1).and_then(|i| names.nth(i)).unwrap_or("").to_string();
+            match kind.as_str() {
+                "keyboard-model" => keyboard.model = name(&mut rules.models.iter().map(|m| m.name.as_str())),
+                "keyboard-layout" => {
+                    keyboard.layout = name(&mut rules.layouts.iter().map(|l| l.name.as_str()));
+                    // Variants belong to a layout
+                    keyboard.variant.clear();
+                }
+                "keyboard-variant" => {
+                    let layout = primary(&keyboard.layout).to_string();
+                    keyboard.variant = name(&mut rules.variants_of(&layout).map(|v| v.name.as_str()));
+                }
+                "keyboard-options" => {
+                    let Some(option) = rules.options.get(idx) else { return };
+                    let mut options: Vec<&str> = keyboard.options.split(',').filter(|name| !name.is_empty()).collect();
+                    match options.iter().position(|name| *name == option.name) {
+                        Some(i) => {
+                            options.remove(i);
+                        }
+                        None => options.push(&option.name),
+                    }
+                    keyboard.options = options.join(",");
+                    if let Some(checked) = ui.get_choice_checked().as_any().downcast_ref::<VecModel<bool>>() {
+                        checked.set_row_data(idx, !checked.row_data(idx).unwrap_or(false));
+                    }
+                }
+                _ => return,
+            }
+            let _ = CatacombClient::send_message(keyboard.message());
+            let result = Settings::update(|settings| {
+                settings.keyboard = keyboard.clone();
+            });
+            if let Err(e) = result {
+                eprintln!("Failed to save keyboard settings: {:#}", e);
+            }
+            ui.set_keyboard_values(Rc::new(VecModel::from(keyboard_values(&rules, &keyboard))).into());
+        }
+    });
+
+    // Game Logs: most recently played games first
+    let log_games: Vec<String> = jollypad_core::logs::logged_games().into_iter().take(8).collect();
+    let log_items: Vec<slint::SharedString> = log_games.iter().map(|g| g.into()).collect();
+    ui.set_log_games(Rc::new(VecModel::from(log_items)).into());
+    let log_times: Vec<slint::SharedString> = log_games
+        .iter()
+        .map(|app_id| {
+            let time = jollypad_core::logs::latest_log(app_id).and_then(|path| jollypad_core::logs::launch_time(&path));
+            match time {
+                Some(time) => tr!("Last run {}", time).into(),
+                None => tr!("Last run").into(),
+            }
+        })
+        .collect();
+    ui.set_log_times(Rc::new(VecModel::from(log_times)).into());
+    let icons = IconLoader::new();
+    let log_icons: Vec<slint::Image> = log_games
+        .iter()
+        .map(|app_id| {
+            let icon = cards::load_card(app_id).map(|card| card.icon).unwrap_or_else(|_| app_id.clone());
+            icons
+                .lookup(&icon, 64, 1)
+                .and_then(|path| slint::Image::load_from_path(&path).ok())
+                .unwrap_or_default()
+        })
+        .collect();
+    ui.set_log_icons(Rc::new(VecModel::from(log_icons)).into());
+    {
+        let ui_weak = ui.as_weak();
+        ui.on_open_log(move |idx| {
+            let Some(app_id) = log_games.get(idx.max(0) as usize) else { return };
+            let text = jollypad_core::logs::read_latest_log(app_id, 64 * 1024)
+                .unwrap_or_else(|| tr!("Log file could not be read."));
+            if let Some(ui) = ui_weak.upgrade() {
+                let title = jollypad_core::logs::latest_log(app_id)
+                    .and_then(|p| p.file_stem().map(|s| s.to_string_lossy().into_owned()))
+                    .map(|stamp| format!("{} ({})", app_id, stamp))
+                    .unwrap_or_else(|| app_id.clone());
+                ui.set_log_title(title.into());
+                ui.set_log_text(text.into());
+            }
+        });
+    }
+
+    // App scaling: running apps first, then the cards, then apps that only have a rule left
+    let cards = jollypad_core::pad::get_default_items(&icons);
+    let mut scale_app_ids: Vec<String> = CatacombClient::get_clients()
+        .into_iter()
+        .map(|client| client.app_id)
+        .filter(|app_id| !app_id.is_empty() && !app_id.starts_with("JollyPad-") && !app_id.starts_with("jolly-"))
+        .collect();
+    scale_app_ids.extend(cards.iter().map(|app| app.app_id.clone()));
+    scale_app_ids.extend(settings.scale_rules.iter().map(|rule| rule.app_id.clone()));
+    let mut seen = HashSet::new();
+    scale_app_ids.retain(|app_id| seen.insert(app_id.clone()));
+
+    let card = |app_id: &str| cards.iter().find(|app| app.app_id == app_id);
+    let scale_app_names: Vec<slint::SharedString> = scale_app_ids
+        .iter()
+        .map(|app_id| card(app_id).map_or(app_id.as_str(), |app| app.name.as_str()).into())
+        .collect();
+    let scale_app_icons: Vec<slint::Image> = scale_app_ids
+        .iter()
+        .map(|app_id| {
+            let icon = card(app_id).map_or_else(|| app_id.to_lowercase(), |app| app.icon.clone());
+            icons
+                .lookup(&icon, 64, 1)
+                .and_then(|path| slint::Image::load_from_path(&path).ok())
+                .unwrap_or_default()
+        })
+        .collect();
+    let (kinds, values): (Vec<i32>, Vec<f32>) = scale_app_ids
+        .iter()
+        .map(|app_id| rule_parts(settings.scale_rules.iter().find(|rule| rule.app_id == *app_id).map(|rule| rule.scale)))
+        .unzip();
+    let scale_rule_kinds = Rc::new(VecModel::from(kinds));
+    let scale_rule_values = Rc::new(VecModel::from(values));
+    ui.set_scale_apps(Rc::new(VecModel::from(scale_app_names)).into());
+    ui.set_scale_app_icons(Rc::new(VecModel::from(scale_app_icons)).into());
+    ui.set_scale_rule_kinds(scale_rule_kinds.clone().into());
+    ui.set_scale_rule_values(scale_rule_values.clone().into());
+
+    let scale_app_ids = Rc::new(scale_app_ids);
+    let send_scale_rule = {
+        let scale_app_ids = scale_app_ids.clone();
+        move |idx: i32, kind: i32, value: f32| {
+            let Some(app_id) = scale_app_ids.get(idx.max(0) as usize) else { return };
+            let message = match rule_scale(kind, value) {
+                Some(scale) => ScaleRule { app_id: app_id.clone(), scale }.message(),
+                None => ScaleRule::reset_message(app_id),
+            };
+            let _ = CatacombClient::send_message(message);
+        }
+    };
+    ui.on_preview_scale_rule(send_scale_rule.clone());
+    ui.on_cancel_scale_rule({
+        let send_scale_rule = send_scale_rule.clone();
+        let scale_rule_kinds = scale_rule_kinds.clone();
+        let scale_rule_values = scale_rule_values.clone();
+        move |idx| {
+            let row = idx.max(0) as usize;
+            if let (Some(kind), Some(value)) = (scale_rule_kinds.row_data(row), scale_rule_values.row_data(row)) {
+                send_scale_rule(idx, kind, value);
+            }
+        }
+    });
+    ui.on_save_scale_rule(move |idx, kind, value| {
+        let row = idx.max(0) as usize;
+        let Some(app_id) = scale_app_ids.get(row) else { return };
+        send_scale_rule(idx, kind, value);
+        let result = Settings::update(|settings| {
+            settings.scale_rules.retain(|rule| rule.app_id != *app_id);
+            if let Some(scale) = rule_scale(kind, value) {
+                settings.scale_rules.push(ScaleRule { app_id: app_id.clone(), scale });
+            }
+        });
+        if let Err(e) = result {
+            eprintln!("Failed to save app scale: {:#}", e);
+        }
+        scale_rule_kinds.set_row_data(row, kind);
+        scale_rule_values.set_row_data(row, value);
+    });
+
+    // Themes: applied here right away, the other apps follow the saved settings
+    let themes = theme::load_themes();
+    let theme_names: Vec<slint::SharedString> = themes.iter().map(|t| t.name.as_str().into()).collect();
+    ui.set_theme_names(Rc::new(VecModel::from(theme_names)).into());
+    ui.set_theme_index(themes.iter().position(|t| t.id == current_theme.id).unwrap_or(0) as i32);
+    {
+        let ui_weak = ui.as_weak();
+        ui.on_apply_theme(move |idx| {
+            let Some(theme) = themes.get(idx.max(0) as usize) else { return };
+            let result = Settings::update(|settings| {
+                settings.theme = theme.id.clone();
+            });
+            if let Err(e) = result {
+                eprintln!("Failed to save theme: {:#}", e);
+            }
+            if let Some(ui) = ui_weak.upgrade() {
+                jollypad_ui_kit::apply_theme!(ui, theme);
+                ui.set_theme_index(idx);
+            }
+        });
+    }
+
+    // Languages: this window switches right away, home and nav follow the saved settings
+    let mut language_names: Vec<slint::SharedString> = vec![tr!("Follow System").into()];
+    language_names.extend(i18n::LANGUAGES.iter().map(|(_, name)| (*name).into()));
+    ui.set_language_names(Rc::new(VecModel::from(language_names)).into());
+    let chosen = Settings::load().language;
+    ui.set_language_index(i18n::LANGUAGES.iter().position(|(code, _)| *code == chosen).map_or(0, |i| i as i32 + 1));
+    {
+        let ui_weak = ui.as_weak();
+        ui.on_apply_language(move |idx| {
+            // 0 follows the system, the others are LANGUAGES
+            let code = match idx {
+                0 => "",
+                _ => match i18n::LANGUAGES.get(idx as usize - 1) {
+                    Some((code, _)) => code,
+                    None => return,
+                },
+            };
+            let result = Settings::update(|settings| {
+                settings.language = code.to_string();
+            });
+            if let Err(e) = result {
+                eprintln!("Failed to save language: {:#}", e);
+            }
+            jollypad_ui_kit::init_translations!();
+            if let Some(ui) = ui_weak.upgrade() {
+                ui.set_language_index(idx);
+            }
+        });
+    }
+
+    // Safe area: the calibration screen edits the insets live, this keeps them
+    ui.on_save_safe_area({
+        let ui_weak = ui.as_weak();
+        move || {
+            let Some(ui) = ui_weak.upgrade() else { return };
+            let insets = ui.global::<SafeAreaInsets>();
+            let result = Settings::update(|settings| {
+                settings.safe_area = SafeArea {
+                    top: insets.get_top(),
+                    right: insets.get_right(),
+                    bottom: insets.get_bottom(),
+                    left: insets.get_left(),
+                }
+                .clamped();
+            });
+            if let Err(e) = result {
+                eprintln!("Failed to save safe area: {:#}", e);
+            }
+        }
+    });
+
+    ui.on_save_accessibility({
+        let ui_weak = ui.as_weak();
+        move || {
+            let Some(ui) = ui_weak.upgrade() else { return };
+            let options = ui.global::<Accessibility>();
+            let result = Settings::update(|settings| {
+                settings.accessibility = config::Accessibility {
+                    text_scale: options.get_text_scale(),
+                    high_contrast_focus: options.get_high_contrast_focus(),
+                    reduce_motion: options.get_reduce_motion(),
+                    large_cards: options.get_large_cards(),
+                }
+                .clamped();
+            });
+            if let Err(e) = result {
+                eprintln!("Failed to save accessibility options: {:#}", e);
+            }
+        }
+    });
+
     ui.on_close_requested({
         move || {
             std::process::exit(0);
@@ -174,3 +552,84 @@ fn main() -> Result<(), slint::PlatformError> {
 
     ui.run()
 }
+
+/// A display change on trial, see the confirmation popup.
+struct Trial {
+    keep: Box<dyn Fn()>,
+    revert: Box<dyn Fn()>,
+}
+
+/// The orientation choices; `None` rotates with the device.
+const ORIENTATIONS: [Option<Orientation>; 5] = [
+    None,
+    Some(Orientation::Landscape),
+    Some(Orientation::Portrait),
+    Some(Orientation::InverseLandscape),
+    Some(Orientation::InversePortrait),
+];
+
+fn orientation_name(orientation: Option<Orientation>) -> String {
+    match orientation {
+        None => tr!("Auto-rotate"),
+        Some(Orientation::Landscape) => tr!("Landscape"),
+        Some(Orientation::Portrait) => tr!("Portrait"),
+        Some(Orientation::InverseLandscape) => tr!("Landscape (Upside Down)"),
+        Some(Orientation::InversePortrait) => tr!("Portrait (Upside Down)"),
+    }
+}
+
+fn lock_orientation(orientation: Option<Orientation>) {
+    let _ = CatacombClient::send_message(IpcMessage::Orientation { lock: orientation, unlock: orientation.is_none() });
+}
+
+/// The first of a comma-separated list of layouts or variants, the one the lists edit.
+fn primary(names: &str) -> &str {
+    names.split(',').next().unwrap_or("")
+}
+
+/// The keyboard model, layout, variant and options as shown on the keyboard page.
+fn keyboard_values(rules: &XkbRules, keyboard: &config::Keyboard) -> Vec<slint::SharedString> {
+    let describe = |items: &[XkbItem], names: &str| {
+        names.split(',').map(|name| XkbRules::describe(items, name)).collect::<Vec<_>>().join(", ")
+    };
+    let layout = primary(&keyboard.layout);
+    let variant = rules
+        .variants_of(layout)
+        .find(|v| v.name == primary(&keyboard.variant))
+        .map_or(primary(&keyboard.variant), |v| v.description.as_str());
+    let or_default = |value: String, default: String| if value.is_empty() { default } else { value };
+    vec![
+        or_default(describe(&rules.models, &keyboard.model), tr!("System Default")).into(),
+        or_default(describe(&rules.layouts, &keyboard.layout), tr!("System Default")).into(),
+        or_default(variant.to_string(), tr!("System Default")).into(),
+        or_default(describe(&rules.options, &keyboard.options), tr!("None")).into(),
+    ]
+}
+
+/// The rule kind and value the app scaling page shows for a window scale;
+/// kind 0 follows the display.
+fn rule_parts(scale: Option<WindowScale>) -> (i32, f32) {
+    let (kind, value) = match scale {
+        None => (0, 1.0),
+        Some(WindowScale::Fixed(value)) => (1, value),
+        Some(WindowScale::Additive(value)) => (2, value),
+        Some(WindowScale::Subtractive(value)) => (3, value),
+        Some(WindowScale::Multiplicative(value)) => (4, value),
+        Some(WindowScale::Divisive(value)) => (5, value),
+    };
+    (kind, value as f32)
+}
+
+/// The window scale of a rule kind and value, see [`rule_parts`].
+fn rule_scale(kind: i32, value: f32) -> Option<WindowScale> {
+    // The page steps in hundredths, which f32 does not hold exactly
+    let value = (value as f64 * 100.0).round() / 100.0;
+    match kind {
+        1 => Some(WindowScale::Fixed(value)),
+        2 => Some(WindowScale::Additive(value)),
+        3 => Some(WindowScale::Subtractive(value)),
+        4 => Some(WindowScale::Multiplicative(value)),
+        5 => Some(WindowScale::Divisive(value)),
+        _ => None,
+    }
+}
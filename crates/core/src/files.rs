@@ -0,0 +1,170 @@
+//! Directory model behind the gamepad file picker.
+//!
+//! Lists folders and matching files for the ui-kit `FilePicker` and provides
+//! its bookmarks: home, `~/Games` and mounted removable media.
+
+use anyhow::{Context, Result};
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// Extensions shown with an image thumbnail.
+pub const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "svg", "webp", "bmp", "gif"];
+/// Programs a card can start: Windows executables, scripts and AppImages.
+/// Native programs without an extension are found by their executable bit.
+pub const PROGRAM_EXTENSIONS: &[&str] = &["exe", "sh", "appimage", "x86_64", "bat", "msi"];
+
+/// Which files a listing shows; folders are always shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFilter {
+    All,
+    Images,
+    /// See [`is_program`].
+    Programs,
+}
+
+impl FileFilter {
+    pub fn matches(self, path: &Path) -> bool {
+        match self {
+            Self::All => true,
+            Self::Images => IMAGE_EXTENSIONS.contains(&extension_of(path).as_str()),
+            Self::Programs => is_program(path),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub is_image: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bookmark {
+    pub label: String,
+    pub path: PathBuf,
+}
+
+fn extension_of(path: &Path) -> String {
+    path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase()
+}
+
+/// Whether `path` is a program a card can start: a file with one of the
+/// [`PROGRAM_EXTENSIONS`] or a regular file with the executable bit set.
+/// Shared libraries often carry the bit too and are left out.
+pub fn is_program(path: &Path) -> bool {
+    if PROGRAM_EXTENSIONS.contains(&extension_of(path).as_str()) {
+        return true;
+    }
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    if name.ends_with(".so") || name.contains(".so.") {
+        return false;
+    }
+    fs::metadata(path).is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+/// Folders and files of `dir` accepted by `filter`, folders first, each
+/// sorted by name. Hidden entries are skipped. A `..` entry leads to the parent.
+pub fn list_dir(dir: &Path, filter: FileFilter) -> Result<Vec<DirEntry>> {
+    let entries = fs::read_dir(dir).with_context(|| format!("Failed to open {:?}", dir))?;
+    let mut listed: Vec<DirEntry> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            if name.starts_with('.') {
+                return None;
+            }
+            let path = entry.path();
+            // Follows symlinks, so linked folders can be browsed
+            let is_dir = path.is_dir();
+            if !is_dir && !filter.matches(&path) {
+                return None;
+            }
+            let is_image = !is_dir && IMAGE_EXTENSIONS.contains(&extension_of(&path).as_str());
+            Some(DirEntry { name, path, is_dir, is_image })
+        })
+        .collect();
+    listed.sort_by_cached_key(|e| (!e.is_dir, e.name.to_lowercase()));
+
+    if let Some(parent) = dir.parent() {
+        listed.insert(0, DirEntry { name: "..".to_string(), path: parent.to_path_buf(), is_dir: true, is_image: false });
+    }
+    Ok(listed)
+}
+
+/// Starting points shown next to the listing.
+pub fn bookmarks() -> Vec<Bookmark> {
+    let mut bookmarks = Vec::new();
+    if let Some(home) = dirs::home_dir() {
+        bookmarks.push(Bookmark { label: crate::tr!("Home"), path: home.clone() });
+        let games = home.join("Games");
+        if games.is_dir() {
+            bookmarks.push(Bookmark { label: crate::tr!("Games"), path: games });
+        }
+    }
+    bookmarks.extend(removable_media());
+    bookmarks
+}
+
+/// Mounted removable media, as mounted by udisks (`/run/media/$USER`) or
+/// older automounters (`/media/$USER`, `/media`).
+fn removable_media() -> Vec<Bookmark> {
+    let user = env::var("USER").unwrap_or_default();
+    let mut roots = Vec::new();
+    if !user.is_empty() {
+        roots.push(Path::new("/run/media").join(&user));
+        roots.push(Path::new("/media").join(&user));
+    }
+    roots.push(PathBuf::from("/media"));
+
+    let mut media = Vec::new();
+    for root in roots {
+        let Ok(entries) = fs::read_dir(&root) else { continue };
+        let mut mounts: Vec<PathBuf> = entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect();
+        mounts.sort();
+        for path in mounts {
+            // /media/$USER itself is a root, not a medium
+            if path.file_name().and_then(|n| n.to_str()) == Some(user.as_str()) {
+                continue;
+            }
+            if media.iter().any(|b: &Bookmark| b.path == path) {
+                continue;
+            }
+            let label = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            media.push(Bookmark { label, path });
+        }
+    }
+    media
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_dir_filters_and_sorts() {
+        let dir = env::temp_dir().join(format!("jollypad-files-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("b-folder")).unwrap();
+        fs::create_dir_all(dir.join(".hidden")).unwrap();
+        for file in ["Game.EXE", "a.txt", "cover.png", "z.sh", "native", "libgame.so.1"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        for file in ["native", "libgame.so.1"] {
+            fs::set_permissions(dir.join(file), fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let names: Vec<String> = list_dir(&dir, FileFilter::Programs).unwrap().into_iter().map(|e| e.name).collect();
+        assert_eq!(names, ["..", "b-folder", "Game.EXE", "native", "z.sh"]);
+
+        let images = list_dir(&dir, FileFilter::Images).unwrap();
+        assert!(images.iter().any(|e| e.name == "cover.png" && e.is_image));
+
+        let all = list_dir(&dir, FileFilter::All).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(all.len(), 8);
+    }
+}
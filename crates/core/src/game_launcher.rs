@@ -9,6 +9,10 @@ use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 use tar::Archive;
 
+use crate::logs;
+use crate::pad::card_format::Card;
+use crate::pad::AppItem;
+use crate::session;
 use crate::CatacombClient;
 
 #[derive(Deserialize, Debug)]
@@ -30,29 +34,86 @@ pub struct GameLaunchInfo {
     pub envs: HashMap<String, String>,
 }
 
-pub fn get_running_game() -> Option<String> {
-    let clients = CatacombClient::get_clients();
-    for client in clients {
-        if !client.app_id.is_empty() {
-             if let Ok(is_game) = is_game_app(&client.app_id) {
-                 if is_game {
-                     return Some(client.app_id);
-                 }
-             }
-        }
+/// Shell snippet sourced by the launch wrapper. `run_hook <label> <command>` runs
+/// a hook through `sh -c`; its output lands in the launch log like the game's.
+const HOOK_RUNNER: &str = r#"run_hook() {
+    printf '[%s] %s: %s\n' "$(date '+%Y-%m-%d %H:%M:%S')" "$1" "$2"
+    sh -c "$2"
+    # Not `status`, which holds the game's exit status once hooks run after it
+    hook_status=$?
+    printf '[%s] %s exited with %s\n' "$(date '+%Y-%m-%d %H:%M:%S')" "$1" "$hook_status"
+}
+"#;
+
+/// Appends Proton's own log files to the launch log once the game has exited.
+const PROTON_LOG_COLLECTOR: &str = r#"if [ -n "$PROTON_LOG_DIR" ]; then
+    for f in "$PROTON_LOG_DIR"/steam-*.log; do
+        [ -f "$f" ] || continue
+        printf '\n===== Proton log: %s =====\n' "${f##*/}"
+        cat "$f"
+        rm -f "$f"
+    done
+fi
+"#;
+
+/// Whether a card or window app_id is a game: `.ini` cards say so themselves,
+/// other cards in `items` by their Game category.
+pub fn is_game(app_id: &str, items: &[AppItem]) -> bool {
+    items.iter().any(|item| item.app_id == app_id && item.is_game()) || is_game_app(app_id).unwrap_or(false)
+}
+
+/// A game found by [`get_running_game`].
+#[derive(Debug, Clone)]
+pub struct RunningGame {
+    /// Card id, to pass to [`close_running_game`].
+    pub card_id: String,
+    /// Name of the card, for asking the user; the id if there is no card to name it.
+    pub name: String,
+}
+
+/// The running game, preferring tracked sessions over window app_ids.
+pub fn get_running_game(items: &[AppItem]) -> Option<RunningGame> {
+    let card_id = session::running_cards()
+        .into_iter()
+        .find(|card_id| is_game(card_id, items))
+        .or_else(|| {
+            // Fallback for games started outside the launch wrapper, which includes all
+            // imported games: Steam windows have the card's `steam_app_<id>` app_id
+            CatacombClient::get_clients()
+                .into_iter()
+                .map(|client| client.app_id)
+                .find(|app_id| !app_id.is_empty() && is_game(app_id, items))
+        })?;
+    // Hidden cards are not in `items`, but `.ini` ones still have a name
+    let name = items
+        .iter()
+        .find(|item| item.app_id == card_id)
+        .map(|item| item.name.clone())
+        .or_else(|| card_path(&card_id).and_then(|path| Card::load(&path).ok()).map(|card| card.name))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| card_id.clone());
+    Some(RunningGame { card_id, name })
+}
+
+/// Stop a game returned by [`get_running_game`], killing its whole process tree
+/// when the session is tracked and closing its window otherwise.
+pub fn close_running_game(card_id: &str) {
+    if !session::terminate(card_id) {
+        CatacombClient::close_window(card_id);
     }
-    None
+}
+
+/// `~/.jolly/app/<app_id>.ini`, if it exists.
+fn card_path(app_id: &str) -> Option<PathBuf> {
+    let path = dirs::home_dir()?.join(".jolly").join("app").join(format!("{}.ini", app_id));
+    path.exists().then_some(path)
 }
 
 pub fn is_game_app(app_id: &str) -> Result<bool> {
-    let home = dirs::home_dir().context("Could not find home directory")?;
-    let jolly_dir = home.join(".jolly");
-    let ini_path = jolly_dir.join("app").join(format!("{}.ini", app_id));
-    if !ini_path.exists() {
-        return Ok(false);
+    match card_path(app_id) {
+        Some(path) => Ok(Card::load(&path)?.is_game),
+        None => Ok(false),
     }
-    let (_, is_game, _) = parse_app_ini(&ini_path)?;
-    Ok(is_game)
 }
 
 pub fn prepare_game_launch(app_id: &str) -> Result<GameLaunchInfo> {
@@ -91,94 +152,85 @@ pub fn prepare_game_launch(app_id: &str) -> Result<GameLaunchInfo> {
         return Err(anyhow::anyhow!("Configuration file not found: {:?}", ini_path));
     }
 
-    let (exec_path, is_game, extra_envs) = parse_app_ini(&ini_path)?;
+    let app = Card::load(&ini_path)?;
     
     // Merge extra envs from INI
-    for (k, v) in extra_envs {
+    for (k, v) in app.env {
         envs.insert(k, v);
     }
-    
-    if is_game {
+
+    // Hooks: global ones from config.ini wrap the per-card ones, and only apply to games
+    let mut pre_launch = Vec::new();
+    let mut post_exit = Vec::new();
+    if app.is_game {
+        pre_launch.extend(config.get("pre-launch").filter(|c| !c.is_empty()).cloned());
+    }
+    pre_launch.extend(app.pre_launch);
+    post_exit.extend(app.post_exit);
+    if app.is_game {
+        post_exit.extend(config.get("post-exit").filter(|c| !c.is_empty()).cloned());
+    }
+
+    let command = if app.is_game {
         // Game mode: use umu-run
         let umu_run = tools_dir.join("umu-run");
-        
-        // Wrap in shell to ensure audio setup
-        // We unload module-raop-discover to prevent AirPlay hijacking which causes no sound
-        // We use sh -c with args to avoid quoting issues
-        let wrapper_script = "pactl unload-module module-raop-discover 2>/dev/null || true; pactl unload-module module-suspend-on-idle 2>/dev/null || true; exec \"$1\" \"$2\"";
-        
-        let args = vec![
-            "-c".to_string(),
-            wrapper_script.to_string(),
-            "game-wrapper".to_string(), // $0
-            umu_run.to_string_lossy().to_string(), // $1
-            exec_path, // $2
-        ];
-        
-        Ok(GameLaunchInfo {
-            program: PathBuf::from("sh"),
-            args,
-            envs,
-        })
+        vec![umu_run.to_string_lossy().to_string(), app.exec]
     } else {
         // App mode: direct exec (via sh)
-        let args = vec!["-c".to_string(), exec_path];
-        Ok(GameLaunchInfo {
-            program: PathBuf::from("sh"),
-            args,
-            envs,
-        })
+        vec!["sh".to_string(), "-c".to_string(), app.exec]
+    };
+
+    let keep_logs = config.get("log-retention").and_then(|v| v.parse().ok()).unwrap_or(10);
+    let launch_log = logs::new_launch_log(app_id, keep_logs)?;
+    let session_file = session::session_file(app_id);
+    if let Some(dir) = session_file.parent() {
+        if let Err(e) = fs::create_dir_all(dir) {
+            eprintln!("Failed to create session directory: {}", e);
+        }
     }
+    envs.insert("JOLLYPAD_APP_ID".to_string(), app_id.to_string());
+    envs.insert("JOLLYPAD_LOG".to_string(), launch_log.to_string_lossy().to_string());
+    envs.insert("JOLLYPAD_SESSION_FILE".to_string(), session_file.to_string_lossy().to_string());
+
+    // Wrap in shell so hooks run around the game process and the session is tracked.
+    // We use sh -c with args to avoid quoting issues
+    let mut args = vec![
+        "-c".to_string(),
+        wrapper_script(&pre_launch, &post_exit),
+        "game-wrapper".to_string(), // $0
+    ];
+    args.extend(command);
+
+    Ok(GameLaunchInfo {
+        program: PathBuf::from("sh"),
+        args,
+        envs,
+    })
 }
 
-fn parse_app_ini(path: &Path) -> Result<(String, bool, HashMap<String, String>)> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    let mut exec = String::new();
-    let mut is_game = false;
-    let mut envs = HashMap::new();
-    let mut in_env_section = false;
-
-    for line in reader.lines() {
-        let line = line?;
-        let t = line.trim();
-        if t.starts_with('[') && t.ends_with(']') {
-            let section = t[1..t.len()-1].trim();
-            if section.eq_ignore_ascii_case("Game") {
-                is_game = true;
-                in_env_section = false;
-            } else if section.eq_ignore_ascii_case("Env") {
-                in_env_section = true;
-            } else {
-                in_env_section = false;
-            }
-            continue;
-        }
-        
-        if let Some((k,v)) = t.split_once('=') {
-            let key = k.trim();
-            let val = v.trim();
-            if in_env_section {
-                // Handle quoted values if necessary, but simple trim for now
-                // Remove surrounding quotes if present
-                let clean_val = val.trim_matches('"').trim_matches('\'').to_string();
-                envs.insert(key.to_string(), clean_val);
-            } else {
-                if key == "Exec" {
-                    exec = val.to_string();
-                }
-            }
-        }
+/// Build the `sh -c` wrapper that redirects all output to `$JOLLYPAD_LOG`, records
+/// the session in `$JOLLYPAD_SESSION_FILE` (see [`session`]), runs pre-launch hooks,
+/// the command passed as positional arguments, and then post-exit hooks. The
+/// wrapper exits with the command's status.
+fn wrapper_script(pre_launch: &[String], post_exit: &[String]) -> String {
+    let mut script = String::from("exec >>\"$JOLLYPAD_LOG\" 2>&1\n");
+    script.push_str(HOOK_RUNNER);
+    script.push_str("printf 'pid=%s\\nstarted=%s\\n' \"$$\" \"$(date +%s)\" >\"$JOLLYPAD_SESSION_FILE\"\n");
+    script.push_str("printf '[%s] launching: %s\\n' \"$(date '+%Y-%m-%d %H:%M:%S')\" \"$*\"\n");
+    for hook in pre_launch {
+        script.push_str(&format!("run_hook pre-launch {}\n", shell_words::quote(hook)));
     }
-    
-    if exec.is_empty() {
-        return Err(anyhow::anyhow!("No Exec line found in {:?}", path));
+    script.push_str("\"$@\"\nstatus=$?\n");
+    script.push_str("printf 'exit=%s\\nended=%s\\n' \"$status\" \"$(date +%s)\" >>\"$JOLLYPAD_SESSION_FILE\"\n");
+    script.push_str("printf '[%s] exited with %s\\n' \"$(date '+%Y-%m-%d %H:%M:%S')\" \"$status\"\n");
+    script.push_str(PROTON_LOG_COLLECTOR);
+    for hook in post_exit {
+        script.push_str(&format!("run_hook post-exit {}\n", shell_words::quote(hook)));
     }
-    
-    Ok((exec, is_game, envs))
+    script.push_str("exit $status\n");
+    script
 }
 
-
 fn load_config(jolly_dir: &Path) -> Result<HashMap<String, String>> {
     let mut config = HashMap::new();
     
@@ -189,6 +241,13 @@ fn load_config(jolly_dir: &Path) -> Result<HashMap<String, String>> {
     config.insert("enable-wow64".to_string(), "True".to_string());
     config.insert("enable-proton-log".to_string(), "True".to_string());
     config.insert("default-runner".to_string(), "Proton-GE Latest".to_string());
+    // Unload module-raop-discover to prevent AirPlay hijacking which causes no sound
+    config.insert(
+        "pre-launch".to_string(),
+        "pactl unload-module module-raop-discover 2>/dev/null || true; pactl unload-module module-suspend-on-idle 2>/dev/null || true".to_string(),
+    );
+    config.insert("post-exit".to_string(), String::new());
+    config.insert("log-retention".to_string(), "10".to_string());
 
     let config_path = jolly_dir.join("config.ini");
 
@@ -235,6 +294,12 @@ fn set_environment_vars(envs: &mut HashMap<String, String>, config: &HashMap<Str
 
     if config.get("enable-proton-log").map(|v| v == "True").unwrap_or(false) {
         envs.insert("PROTON_LOG".to_string(), "1".to_string());
+        // Collected into the launch log by the wrapper after the game exits
+        let proton_log_dir = logs::game_log_dir(app_id).join("proton");
+        if let Err(e) = fs::create_dir_all(&proton_log_dir) {
+            eprintln!("Failed to create Proton log directory: {}", e);
+        }
+        envs.insert("PROTON_LOG_DIR".to_string(), proton_log_dir.to_string_lossy().to_string());
     }
 
     // Audio fixes for Proton:
@@ -514,3 +579,35 @@ fn check_and_update_components(components_dir: &Path) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrapper_quotes_hooks() {
+        let script = wrapper_script(&["echo 'pre'".to_string()], &[]);
+        assert!(script.contains("run_hook pre-launch 'echo '\\''pre'\\'''\n\"$@\"\n"));
+        assert!(!script.contains("run_hook post-exit"));
+    }
+
+    #[test]
+    fn test_wrapper_logs_and_records_session() {
+        let script = wrapper_script(&[], &["notify-send done".to_string()]);
+        assert!(!script.contains("run_hook pre-launch"));
+        assert!(script.starts_with("exec >>\"$JOLLYPAD_LOG\" 2>&1\n"));
+        assert!(script.contains("\"$@\"\nstatus=$?\nprintf 'exit=%s\\nended=%s\\n' \"$status\""));
+        assert!(script.contains("\nrun_hook post-exit 'notify-send done'\nexit $status\n"));
+        assert!(script.ends_with("exit $status\n"));
+    }
+
+    #[test]
+    fn test_hook_exit_status() {
+        let script = format!("{}run_hook post-exit 'exit 3'\n", HOOK_RUNNER);
+        for shell in ["sh", "bash"] {
+            let Ok(output) = std::process::Command::new(shell).arg("-c").arg(&script).output() else { continue };
+            let log = String::from_utf8_lossy(&output.stdout);
+            assert!(log.contains("post-exit exited with 3"), "{}: {}", shell, log);
+        }
+    }
+}
@@ -0,0 +1,276 @@
+//! Translations for strings built in Rust.
+//!
+//! The catalogs are the gettext `.po` files in `translations/<language>/LC_MESSAGES`,
+//! one per app, that Slint bundles for `@tr()`. Messages from `.slint` files
+//! carry the component name as `msgctxt`; the ones used from Rust have none.
+//! Source strings are English, so English needs no translations.
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Display;
+use std::sync::RwLock;
+
+use crate::settings::Settings;
+
+/// Languages with a catalog: code and name in that language.
+pub const LANGUAGES: &[(&str, &str)] = &[("en", "English"), ("zh_CN", "简体中文")];
+
+#[derive(Debug, Default)]
+pub struct Catalog {
+    // (msgctxt, msgid) -> msgstr
+    messages: HashMap<(String, String), String>,
+}
+
+/// Undo the C escapes of a quoted `.po` string, given without its quotes.
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+fn quoted(text: &str) -> Option<String> {
+    let inner = text.trim().strip_prefix('"')?.strip_suffix('"')?;
+    Some(unescape(inner))
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Context,
+    Id,
+    Str,
+    // msgid_plural and the other plural forms
+    Ignored,
+}
+
+#[derive(Default)]
+struct Entry {
+    context: String,
+    msgid: String,
+    msgstr: String,
+    fuzzy: bool,
+}
+
+impl Catalog {
+    /// Read a `.po` file. Plural messages keep their first form, which is
+    /// the only one in Chinese; untranslated and fuzzy entries are skipped.
+    pub fn parse(po: &str) -> Self {
+        let mut catalog = Self::default();
+        let mut entry = Entry::default();
+        let mut field = Field::Ignored;
+        // Flags are written before the entry they belong to
+        let mut fuzzy = false;
+
+        for line in po.lines().map(str::trim) {
+            if let Some(flags) = line.strip_prefix("#,") {
+                fuzzy = flags.split(',').any(|flag| flag.trim() == "fuzzy");
+                continue;
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (keyword, text) = match line.find(' ') {
+                Some(pos) if !line.starts_with('"') => (&line[..pos], &line[pos + 1..]),
+                _ => ("", line),
+            };
+            let next = match keyword {
+                "msgctxt" => Field::Context,
+                "msgid" => Field::Id,
+                "msgstr" | "msgstr[0]" => Field::Str,
+                "" => field,
+                _ => Field::Ignored,
+            };
+            // A context, or a msgid without one, starts the next entry
+            let starts_entry = next == Field::Context || (next == Field::Id && field != Field::Context);
+            if !keyword.is_empty() && starts_entry {
+                catalog.add(std::mem::take(&mut entry));
+                entry.fuzzy = std::mem::take(&mut fuzzy);
+            }
+            field = next;
+            let Some(text) = quoted(text) else { continue };
+            match field {
+                Field::Context => entry.context.push_str(&text),
+                Field::Id => entry.msgid.push_str(&text),
+                Field::Str => entry.msgstr.push_str(&text),
+                Field::Ignored => {}
+            }
+        }
+        catalog.add(entry);
+        catalog
+    }
+
+    fn add(&mut self, entry: Entry) {
+        if !entry.msgid.is_empty() && !entry.msgstr.is_empty() && !entry.fuzzy {
+            self.messages.insert((entry.context, entry.msgid), entry.msgstr);
+        }
+    }
+
+    pub fn get(&self, context: &str, msgid: &str) -> Option<&str> {
+        self.messages.get(&(context.to_string(), msgid.to_string())).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+}
+
+static CATALOG: RwLock<Option<Catalog>> = RwLock::new(None);
+
+/// Use `catalog` for [`tr`] from now on.
+pub fn set_catalog(catalog: Catalog) {
+    if let Ok(mut current) = CATALOG.write() {
+        *current = Some(catalog);
+    }
+}
+
+/// The translation of `msgid`, or `msgid` itself.
+pub fn tr(msgid: &str) -> String {
+    let catalog = CATALOG.read();
+    catalog
+        .ok()
+        .and_then(|catalog| catalog.as_ref().and_then(|c| c.get("", msgid)).map(str::to_string))
+        .unwrap_or_else(|| msgid.to_string())
+}
+
+/// Fill the placeholders of a translated message like Slint's `@tr()` does:
+/// `{}` takes the next argument, `{0}`, `{1}`... a given one, `{{` and `}}`
+/// are literal braces.
+pub fn format(template: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut next = 0;
+    let mut rest = template;
+    while let Some(pos) = rest.find(['{', '}']) {
+        out.push_str(&rest[..pos]);
+        let tail = &rest[pos..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            out.push_str(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+        let Some(end) = tail.find('}').filter(|_| tail.starts_with('{')) else {
+            out.push_str(&tail[..1]);
+            rest = &tail[1..];
+            continue;
+        };
+        let index = match &tail[1..end] {
+            "" => {
+                next += 1;
+                Some(next - 1)
+            }
+            digits => digits.parse::<usize>().ok(),
+        };
+        match index.and_then(|i| args.get(i)) {
+            Some(arg) => out.push_str(&arg.to_string()),
+            None => out.push_str(&tail[..=end]),
+        }
+        rest = &tail[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Translate a message and fill its placeholders, see [`format`]:
+///
+/// ```ignore
+/// tr!("{} is currently running", name)
+/// ```
+#[macro_export]
+macro_rules! tr {
+    ($msgid:literal) => {
+        $crate::i18n::tr($msgid)
+    };
+    ($msgid:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::format(&$crate::i18n::tr($msgid), &[$(&$arg as &dyn ::std::fmt::Display),+])
+    };
+}
+
+/// The catalog language for a locale such as `zh_CN.UTF-8` or `zh`; `None`
+/// for languages without one.
+pub fn language_for_locale(locale: &str) -> Option<&'static str> {
+    let locale = locale.split(['.', '@']).next().unwrap_or("");
+    let language = locale.split(['_', '-']).next().unwrap_or("").to_ascii_lowercase();
+    LANGUAGES
+        .iter()
+        .map(|(code, _)| *code)
+        .find(|code| code.eq_ignore_ascii_case(&locale.replace('-', "_")))
+        .or_else(|| LANGUAGES.iter().map(|(code, _)| *code).find(|code| code.split('_').next() == Some(language.as_str())))
+}
+
+/// The language to show: the one picked in the settings, else the session
+/// locale (`LC_ALL`, `LC_MESSAGES`, then `LANG`), else English.
+pub fn language() -> &'static str {
+    let chosen = Settings::load().language;
+    if let Some(code) = LANGUAGES.iter().map(|(code, _)| *code).find(|code| *code == chosen) {
+        return code;
+    }
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|locale| language_for_locale(&locale))
+        .unwrap_or("en")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_catalog() {
+        let po = r#"
+msgid ""
+msgstr ""
+"Content-Type: text/plain; charset=UTF-8\n"
+
+msgctxt "MainWindow"
+msgid "Search"
+msgstr "搜索"
+
+msgid "Home"
+msgstr "主页"
+
+#, fuzzy
+msgid "Power"
+msgstr "电源"
+
+msgid "Untranslated"
+msgstr ""
+
+msgctxt "SettingsWindow"
+msgid "Reverting in {n} second"
+msgid_plural "Reverting in {n} seconds"
+msgstr[0] "{n} 秒后"
+"还原"
+"#;
+        let catalog = Catalog::parse(po);
+        assert_eq!(catalog.len(), 3);
+        assert_eq!(catalog.get("MainWindow", "Search"), Some("搜索"));
+        assert_eq!(catalog.get("", "Home"), Some("主页"));
+        assert_eq!(catalog.get("", "Power"), None);
+        assert_eq!(catalog.get("SettingsWindow", "Reverting in {n} second"), Some("{n} 秒后还原"));
+
+        assert_eq!(format("{} is running, start {}?", &[&"a", &"b"]), "a is running, start b?");
+        assert_eq!(format("{1} {0} {{x}}", &[&1, &2]), "2 1 {x}");
+        assert_eq!(format("{5}", &[]), "{5}");
+
+        assert_eq!(language_for_locale("zh_CN.UTF-8"), Some("zh_CN"));
+        assert_eq!(language_for_locale("zh_TW"), Some("zh_CN"));
+        assert_eq!(language_for_locale("en_GB"), Some("en"));
+        assert_eq!(language_for_locale("de_DE"), None);
+    }
+}
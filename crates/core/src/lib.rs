@@ -4,6 +4,14 @@ pub mod ipc;
 pub mod clients;
 pub mod catacomb_client;
 pub mod game_launcher;
+pub mod session;
+pub mod logs;
+pub mod files;
+pub mod thumbnails;
+pub mod settings;
+pub mod theme;
+pub mod i18n;
+pub mod xkb;
 
 // Re-export common types if needed
 pub use pad::get_default_items as get_pad_items;
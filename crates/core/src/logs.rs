@@ -0,0 +1,214 @@
+//! Per-launch game logs under `~/.local/state/jollypad/logs/games/<app_id>/`.
+//!
+//! Every launch through the game wrapper gets its own `<timestamp>.log` holding
+//! hook output, the game's stdout/stderr and, when enabled, the Proton log.
+
+use anyhow::{Context, Result};
+use std::env;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// `$XDG_STATE_HOME/jollypad`, falling back to `~/.local/state/jollypad`.
+pub fn state_dir() -> PathBuf {
+    env::var("XDG_STATE_HOME")
+        .ok()
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|h| h.join(".local").join("state")))
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("jollypad")
+}
+
+pub fn games_log_dir() -> PathBuf {
+    state_dir().join("logs").join("games")
+}
+
+pub fn game_log_dir(app_id: &str) -> PathBuf {
+    games_log_dir().join(app_id)
+}
+
+/// Create the log path for a new launch of `app_id`, deleting the oldest logs so
+/// that at most `keep` remain afterwards.
+pub fn new_launch_log(app_id: &str, keep: usize) -> Result<PathBuf> {
+    let dir = game_log_dir(app_id);
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create log directory {:?}", dir))?;
+
+    let logs = list_logs(&dir);
+    let excess = (logs.len() + 1).saturating_sub(keep.max(1));
+    for old in logs.iter().take(excess) {
+        if let Err(e) = fs::remove_file(old) {
+            eprintln!("Failed to remove old log {:?}: {}", old, e);
+        }
+    }
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let mut path = dir.join(format!("{}.log", format_timestamp(now)));
+    // Two launches within the same second
+    let mut n = 1;
+    while path.exists() {
+        path = dir.join(format!("{}-{}.log", format_timestamp(now), n));
+        n += 1;
+    }
+    Ok(path)
+}
+
+/// App ids that have at least one log, most recently played first.
+pub fn logged_games() -> Vec<String> {
+    let mut games: Vec<(String, PathBuf)> = Vec::new();
+    if let Ok(entries) = fs::read_dir(games_log_dir()) {
+        for entry in entries.flatten() {
+            let Ok(app_id) = entry.file_name().into_string() else { continue };
+            if let Some(latest) = list_logs(&entry.path()).pop() {
+                games.push((app_id, latest));
+            }
+        }
+    }
+    games.sort_by_cached_key(|(_, latest)| std::cmp::Reverse(log_order(latest)));
+    games.into_iter().map(|(app_id, _)| app_id).collect()
+}
+
+pub fn latest_log(app_id: &str) -> Option<PathBuf> {
+    list_logs(&game_log_dir(app_id)).pop()
+}
+
+/// Read the end of the latest log of `app_id`, at most `max_bytes` long.
+pub fn read_latest_log(app_id: &str, max_bytes: u64) -> Option<String> {
+    let path = latest_log(app_id)?;
+    let mut file = fs::File::open(&path).ok()?;
+    let len = file.metadata().ok()?.len();
+    let mut truncated = false;
+    if len > max_bytes {
+        file.seek(SeekFrom::Start(len - max_bytes)).ok()?;
+        truncated = true;
+    }
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).ok()?;
+    let text = String::from_utf8_lossy(&bytes);
+    if truncated {
+        // Drop the partial first line
+        let text = text.split_once('\n').map_or(text.as_ref(), |(_, rest)| rest);
+        return Some(format!("[…]\n{}", text));
+    }
+    Some(text.into_owned())
+}
+
+/// When the launch of a log started, as `YYYY-MM-DD HH:MM` in local time.
+pub fn launch_time(path: &Path) -> Option<String> {
+    let (stamp, _) = log_order(path);
+    let tm = local_time(parse_timestamp(&stamp)?)?;
+    Some(format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        tm.tm_year + 1900, tm.tm_mon + 1, tm.tm_mday, tm.tm_hour, tm.tm_min
+    ))
+}
+
+/// Broken-down local time of a unix timestamp, in the zone of `TZ` or `/etc/localtime`.
+fn local_time(secs: i64) -> Option<libc::tm> {
+    let time = libc::time_t::try_from(secs).ok()?;
+    // SAFETY: `tm` is plain data, and localtime_r only writes to the `tm` passed in
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::localtime_r(&time, &mut tm) };
+    (!result.is_null()).then_some(tm)
+}
+
+/// Log files in `dir`, oldest first, see [`log_order`].
+fn list_logs(dir: &Path) -> Vec<PathBuf> {
+    let mut logs: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries.flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_file() && p.extension().is_some_and(|x| x == "log"))
+                .collect()
+        })
+        .unwrap_or_default();
+    logs.sort_by_cached_key(|path| log_order(path));
+    logs
+}
+
+/// Sort key of a `<timestamp>.log` or `<timestamp>-<n>.log` name: launches in
+/// the same second count up from the one without a suffix, and `-10` comes after `-9`.
+fn log_order(path: &Path) -> (String, u32) {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    // `YYYYMMDD-HHMMSS` is 15 characters
+    match stem.get(15..).and_then(|rest| rest.strip_prefix('-')) {
+        Some(n) => (stem[..15].to_string(), n.parse().unwrap_or(0)),
+        None => (stem.to_string(), 0),
+    }
+}
+
+/// Format a unix timestamp as `YYYYMMDD-HHMMSS` (UTC).
+fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let rem = secs % 86400;
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year, month, day, rem / 3600, (rem % 3600) / 60, rem % 60
+    )
+}
+
+/// The unix timestamp of a `YYYYMMDD-HHMMSS` name, see [`format_timestamp`].
+fn parse_timestamp(stamp: &str) -> Option<i64> {
+    let number = |range: std::ops::Range<usize>| {
+        stamp.get(range).filter(|s| s.bytes().all(|b| b.is_ascii_digit())).and_then(|s| s.parse::<i64>().ok())
+    };
+    if stamp.len() != 15 || stamp.as_bytes()[8] != b'-' {
+        return None;
+    }
+    let (year, month, day) = (number(0..4)?, number(4..6)?, number(6..8)?);
+    let (hour, minute, second) = (number(9..11)?, number(11..13)?, number(13..15)?);
+    // Days since 1970-01-01 from the civil date, the inverse of format_timestamp
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+    Some(days * 86400 + hour * 3600 + minute * 60 + second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "19700101-000000");
+        assert_eq!(format_timestamp(951782400), "20000229-000000");
+        assert_eq!(format_timestamp(1792264546), "20261017-191546");
+        for secs in [0, 951782400, 1792264546] {
+            assert_eq!(parse_timestamp(&format_timestamp(secs)), Some(secs as i64));
+        }
+        assert_eq!(parse_timestamp("20261017-1915"), None);
+        let time = launch_time(Path::new("20261017-191546-2.log")).unwrap();
+        assert_eq!(time.len(), "2026-10-17 19:15".len());
+        assert_eq!(launch_time(Path::new("notes.log")), None);
+    }
+
+    #[test]
+    fn test_same_second_launches() {
+        let dir = env::temp_dir().join(format!("jollypad-logs-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["20261017-191546-10.log", "20261017-191546-1.log", "20261017-191547.log", "20261017-191546.log", "20261017-191546-9.log"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+        let logs = list_logs(&dir);
+        let names: Vec<_> = logs.iter().map(|p| p.file_name().unwrap().to_str().unwrap()).collect();
+        assert_eq!(
+            names,
+            ["20261017-191546.log", "20261017-191546-1.log", "20261017-191546-9.log", "20261017-191546-10.log", "20261017-191547.log"]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
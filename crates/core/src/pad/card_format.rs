@@ -0,0 +1,550 @@
+//! The `.ini` card format shared by home, the card editor and game-launcher.
+//!
+//! A card lives in `~/.jolly/app/<app_id>.ini`:
+//!
+//! ```ini
+//! # Lines starting with '#' or ';' are comments
+//! [Game]
+//! Name=Hollow Knight
+//! Exec=/home/deck/Games/hollow-knight/hollow_knight.exe
+//! Icon=/home/deck/Games/hollow-knight/icon.png
+//! Cover=~/Pictures/hollow-knight-cover.jpg
+//! Category=Game
+//! PreLaunch=systemctl --user stop syncthing
+//! PostExit=systemctl --user start syncthing
+//!
+//! [Env]
+//! DXVK_HUD=fps
+//! ```
+//!
+//! - The first `[App]` or `[Game]` section describes the card. `[Game]`, or
+//!   `Type=Game` inside `[App]`, makes it a Windows game run through Proton;
+//!   its `Exec` is then the path to the executable.
+//! - `Name` and `Exec` are required; `Icon` (name or path) and `Category` are
+//!   optional. `Category` may list several categories separated by commas,
+//!   e.g. `Category=Game,Couch`; each one becomes a tab on home.
+//!   `PreLaunch`/`PostExit` are shell commands run around the launch.
+//! - `Cover` (portrait box art), `Hero` (wide banner), `Logo` and `Background`
+//!   are optional image paths used instead of the icon where they fit.
+//! - `[Env]` holds environment variables for the launched program.
+//! - Section names are case-insensitive, keys are not. A repeated key keeps its
+//!   last value. A UTF-8 BOM and indentation are ignored.
+//! - Lines without `=`, keys before the first section and section headers
+//!   without a closing `]` are skipped with a warning, as older JollyPad
+//!   versions silently did.
+//! - `Name`, `Icon`, the artwork, a game's `Exec` and `[Env]` values may be
+//!   wrapped in `"…"` (with `\"`, `\\`, `\n` and `\t` escapes) or `'…'`. An
+//!   app's `Exec` and the hooks are shell command lines and are passed to
+//!   `sh -c` as written. A value with an unbalanced quote or text after the
+//!   closing quote is kept as written, with a warning.
+//!
+//! [`IniDocument`] keeps every line it read, so writing a card back only
+//! touches the keys that changed and leaves comments, skipped lines and
+//! unknown keys alone. Values are written through [`quote`].
+
+use anyhow::{Context, Result};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use super::Artwork;
+
+/// Sections that describe the card itself.
+const CARD_SECTIONS: [&str; 2] = ["App", "Game"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// 1-based line the error was found on, if it belongs to a line.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl ParseError {
+    fn at(line: usize, message: impl Into<String>) -> Self {
+        ParseError { line: Some(line), message: message.into() }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone)]
+enum Line {
+    /// Blank lines, comments and skipped lines, written back verbatim
+    Other(String),
+    Section { name: String, number: usize, raw: String },
+    Entry { key: String, value: String, number: usize, raw: String },
+}
+
+/// An ini file that remembers its layout.
+#[derive(Debug, Clone, Default)]
+pub struct IniDocument {
+    lines: Vec<Line>,
+    /// Why lines were skipped while parsing.
+    skipped: Vec<ParseError>,
+}
+
+impl IniDocument {
+    /// Read any text; lines that make no sense are kept as they are and
+    /// listed in [`skipped`](Self::skipped).
+    pub fn parse(content: &str) -> Self {
+        let mut lines = Vec::new();
+        let mut skipped = Vec::new();
+        let mut in_section = false;
+        for (i, raw) in content.lines().enumerate() {
+            let number = i + 1;
+            // Editors on Windows like to start files with a BOM
+            let text = raw.trim_start_matches('\u{FEFF}').trim();
+            if text.is_empty() || text.starts_with('#') || text.starts_with(';') {
+                lines.push(Line::Other(raw.to_string()));
+                continue;
+            }
+            if let Some(rest) = text.strip_prefix('[') {
+                let name = match rest.strip_suffix(']').map(str::trim) {
+                    None => Err("missing ']' after the section name"),
+                    Some("") => Err("empty section name"),
+                    Some(name) => Ok(name),
+                };
+                let name = match name {
+                    Ok(name) => name,
+                    Err(problem) => {
+                        skipped.push(ParseError::at(number, problem));
+                        lines.push(Line::Other(raw.to_string()));
+                        continue;
+                    }
+                };
+                in_section = true;
+                lines.push(Line::Section { name: name.to_string(), number, raw: raw.to_string() });
+                continue;
+            }
+            let problem = match text.split_once('=') {
+                None => Some(format!("expected 'Key=Value', found '{}'", text)),
+                Some((key, _)) if key.trim().is_empty() => Some("missing key before '='".to_string()),
+                Some((key, _)) if !in_section => Some(format!("'{}' is outside of a section", key.trim())),
+                Some(_) => None,
+            };
+            if let Some(problem) = problem {
+                skipped.push(ParseError::at(number, problem));
+                lines.push(Line::Other(raw.to_string()));
+                continue;
+            }
+            let (key, value) = text.split_once('=').unwrap_or_default();
+            lines.push(Line::Entry { key: key.trim().to_string(), value: value.trim().to_string(), number, raw: raw.to_string() });
+        }
+        IniDocument { lines, skipped }
+    }
+
+    /// Lines that were skipped while parsing, with the reason.
+    pub fn skipped(&self) -> &[ParseError] {
+        &self.skipped
+    }
+
+    /// Section names in file order.
+    pub fn sections(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().filter_map(|line| match line {
+            Line::Section { name, .. } => Some(name.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Line range of the first section called `section`, header included.
+    fn section_range(&self, section: &str) -> Option<(usize, usize)> {
+        let start = self
+            .lines
+            .iter()
+            .position(|l| matches!(l, Line::Section { name, .. } if name.eq_ignore_ascii_case(section)))?;
+        let end = self.lines[start + 1..]
+            .iter()
+            .position(|l| matches!(l, Line::Section { .. }))
+            .map_or(self.lines.len(), |n| start + 1 + n);
+        Some((start, end))
+    }
+
+    fn lines_of(&self, section: &str) -> &[Line] {
+        match self.section_range(section) {
+            Some((start, end)) => &self.lines[start..end],
+            None => &[],
+        }
+    }
+
+    /// Raw (trimmed) value and line number of `key`.
+    fn find(&self, section: &str, key: &str) -> Option<(&str, usize)> {
+        self.lines_of(section).iter().rev().find_map(|line| match line {
+            Line::Entry { key: k, value, number, .. } if k == key => Some((value.as_str(), *number)),
+            _ => None,
+        })
+    }
+
+    /// Raw value of `key` in `section`, without quote handling.
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.find(section, key).map(|(value, _)| value)
+    }
+
+    /// Keys and raw values of `section` in file order.
+    pub fn entries(&self, section: &str) -> Vec<(&str, &str)> {
+        self.lines_of(section)
+            .iter()
+            .filter_map(|line| match line {
+                Line::Entry { key, value, .. } => Some((key.as_str(), value.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Set `key` to the raw `value`. An existing entry is rewritten in place,
+    /// keeping its indentation; a new one goes after the last entry of the
+    /// section, which is appended when missing.
+    pub fn set(&mut self, section: &str, key: &str, value: &str) {
+        let Some((start, end)) = self.section_range(section) else {
+            if !self.lines.is_empty() {
+                self.lines.push(Line::Other(String::new()));
+            }
+            self.lines.push(Line::Section { name: section.to_string(), number: 0, raw: format!("[{}]", section) });
+            self.lines.push(Line::Entry {
+                key: key.to_string(),
+                value: value.to_string(),
+                number: 0,
+                raw: format!("{}={}", key, value),
+            });
+            return;
+        };
+
+        let existing = (start..end)
+            .rev()
+            .find(|&i| matches!(&self.lines[i], Line::Entry { key: k, .. } if k == key));
+        if let Some(i) = existing {
+            if let Line::Entry { value: v, raw, .. } = &mut self.lines[i] {
+                let indent: String = raw.chars().take_while(|c| c.is_whitespace()).collect();
+                *v = value.to_string();
+                *raw = format!("{}{}={}", indent, key, value);
+            }
+            return;
+        }
+        let last_entry = (start..end).rev().find(|&i| matches!(self.lines[i], Line::Entry { .. })).unwrap_or(start);
+        self.lines.insert(
+            last_entry + 1,
+            Line::Entry { key: key.to_string(), value: value.to_string(), number: 0, raw: format!("{}={}", key, value) },
+        );
+    }
+
+    /// Remove every `key` entry from `section`.
+    pub fn remove(&mut self, section: &str, key: &str) {
+        let Some((start, end)) = self.section_range(section) else { return };
+        let mut i = start;
+        let mut end = end;
+        while i < end {
+            if matches!(&self.lines[i], Line::Entry { key: k, .. } if k == key) {
+                self.lines.remove(i);
+                end -= 1;
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    /// Rename the first section called `from`.
+    pub fn rename_section(&mut self, from: &str, to: &str) {
+        if let Some((start, _)) = self.section_range(from) {
+            if let Line::Section { name, raw, .. } = &mut self.lines[start] {
+                *name = to.to_string();
+                *raw = format!("[{}]", to);
+            }
+        }
+    }
+}
+
+impl fmt::Display for IniDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            let raw = match line {
+                Line::Other(raw) | Line::Section { raw, .. } | Line::Entry { raw, .. } => raw,
+            };
+            writeln!(f, "{}", raw)?;
+        }
+        Ok(())
+    }
+}
+
+/// Write `value` so that [`unquote`] reads it back unchanged: as it is when
+/// that works, in `"…"` with escapes when it starts with a quote or has
+/// surrounding whitespace, a newline or a tab.
+pub fn quote(value: &str) -> String {
+    let plain = !value.starts_with(['"', '\'']) && value.trim() == value && !value.contains(['\n', '\t']);
+    if plain {
+        return value.to_string();
+    }
+    let mut out = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Strip `"…"` or `'…'` around a value. Double quotes understand `\"`, `\\`,
+/// `\n` and `\t`; other backslashes are kept, so Windows paths survive.
+pub fn unquote(value: &str) -> Result<String, &'static str> {
+    if let Some(inner) = value.strip_prefix('\'') {
+        return inner.strip_suffix('\'').map(str::to_string).ok_or("missing closing quote");
+    }
+    let Some(inner) = value.strip_prefix('"') else {
+        return Ok(value.to_string());
+    };
+    let mut out = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some(c @ ('"' | '\\')) => out.push(c),
+                Some(c) => {
+                    out.push('\\');
+                    out.push(c);
+                }
+                None => break,
+            },
+            '"' if chars.as_str().trim().is_empty() => return Ok(out),
+            '"' => return Err("unexpected text after the closing quote"),
+            c => out.push(c),
+        }
+    }
+    Err("missing closing quote")
+}
+
+/// The section describing the card: the first `[App]` or `[Game]`.
+pub fn card_section(doc: &IniDocument) -> Option<&str> {
+    doc.sections().find(|name| CARD_SECTIONS.iter().any(|s| name.eq_ignore_ascii_case(s)))
+}
+
+/// A parsed card, see the module docs for the schema.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Card {
+    pub name: String,
+    /// Command line for apps, path to the Windows executable for games.
+    pub exec: String,
+    /// Icon name or path; empty for none.
+    pub icon: String,
+    /// Empty when the card does not set one.
+    pub category: String,
+    pub artwork: Artwork,
+    pub is_game: bool,
+    pub pre_launch: Option<String>,
+    pub post_exit: Option<String>,
+    pub env: Vec<(String, String)>,
+    /// Lines that were skipped and values with broken quotes, to warn about.
+    pub skipped: Vec<ParseError>,
+}
+
+impl Card {
+    pub fn parse(content: &str) -> Result<Self, ParseError> {
+        Self::from_document(&IniDocument::parse(content))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).with_context(|| format!("Failed to read card {:?}", path))?;
+        let card = Self::parse(&content).with_context(|| format!("Invalid card {:?}", path))?;
+        for skipped in &card.skipped {
+            eprintln!("Card {:?}: skipping {}", path, skipped);
+        }
+        Ok(card)
+    }
+
+    pub fn from_document(doc: &IniDocument) -> Result<Self, ParseError> {
+        let section = card_section(doc).ok_or_else(|| ParseError {
+            line: None,
+            message: "no [App] or [Game] section".to_string(),
+        })?;
+        let header = doc
+            .section_range(section)
+            .and_then(|(start, _)| match &doc.lines[start] {
+                Line::Section { number, .. } => Some(*number),
+                _ => None,
+            })
+            .unwrap_or(0);
+        let is_game = section.eq_ignore_ascii_case("Game")
+            || doc.get(section, "Type").is_some_and(|t| t.eq_ignore_ascii_case("Game"));
+
+        let mut skipped = doc.skipped().to_vec();
+        let mut unquoted = |key: &str| match doc.find(section, key) {
+            Some((value, number)) => unquote_or_raw(key, value, number, &mut skipped),
+            None => String::new(),
+        };
+        let non_empty = |value: Option<&str>| value.filter(|v| !v.is_empty()).map(str::to_string);
+
+        let name = unquoted("Name");
+        // A game's Exec is a path handed to Proton, an app's a shell command line
+        let exec = if is_game { unquoted("Exec") } else { doc.get(section, "Exec").unwrap_or_default().to_string() };
+        if name.is_empty() {
+            return Err(ParseError::at(header, format!("[{}] has no Name", section)));
+        }
+        if exec.is_empty() {
+            return Err(ParseError::at(header, format!("[{}] has no Exec", section)));
+        }
+
+        let icon = unquoted("Icon");
+        let artwork = Artwork {
+            cover: unquoted("Cover"),
+            hero: unquoted("Hero"),
+            logo: unquoted("Logo"),
+            background: unquoted("Background"),
+        };
+
+        let mut env = Vec::new();
+        for line in doc.lines_of("Env") {
+            if let Line::Entry { key, value, number, .. } = line {
+                let value = unquote_or_raw(key, value, *number, &mut skipped);
+                env.retain(|(k, _)| k != key);
+                env.push((key.clone(), value));
+            }
+        }
+
+        Ok(Card {
+            name,
+            exec,
+            icon,
+            artwork,
+            category: doc.get(section, "Category").unwrap_or_default().to_string(),
+            is_game,
+            pre_launch: non_empty(doc.get(section, "PreLaunch")),
+            post_exit: non_empty(doc.get(section, "PostExit")),
+            env,
+            skipped,
+        })
+    }
+}
+
+/// [`unquote`] the value of `key`, or keep it as written if its quotes are
+/// broken, as older JollyPad versions did, and note why in `skipped`.
+fn unquote_or_raw(key: &str, value: &str, number: usize, skipped: &mut Vec<ParseError>) -> String {
+    unquote(value).unwrap_or_else(|e| {
+        skipped.push(ParseError::at(number, format!("quotes of {}: {}, keeping the value as written", key, e)));
+        value.to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_quirks() {
+        // BOM before the header, as written by some Windows editors
+        let card = Card::parse("\u{FEFF}[Game]\nName=Test Game\nExec=/games/test.exe").unwrap();
+        assert!(card.is_game);
+        assert_eq!(card.name, "Test Game");
+
+        // A broken section header is skipped, the card still loads
+        let card = Card::parse("[Game]\nName=Test Game\n[Env\nExec=/games/test.exe\n").unwrap();
+        assert_eq!(card.exec, "/games/test.exe");
+        assert_eq!(card.skipped, [ParseError::at(3, "missing ']' after the section name")]);
+
+        // Type=Game inside [App] is a game too; home and game-launcher used to disagree
+        let card = Card::parse("[App]\nName=Old\nType=game\nExec=/games/old.exe\n").unwrap();
+        assert!(card.is_game);
+
+        // Indented, CRLF, comments, a repeated key and an unrelated section
+        let content = "; card\r\n  [app]\r\n  Name=First\r\n  # note\r\n  Name=Test App\r\n  Exec=foot -e htop\r\n[Other]\r\nName=Nope\r\n";
+        let card = Card::parse(content).unwrap();
+        assert!(!card.is_game);
+        assert_eq!(card.name, "Test App");
+        assert_eq!(card.exec, "foot -e htop");
+    }
+
+    #[test]
+    fn test_quoting() {
+        let content = r#"[Game]
+Name="  Spaced \"Out\"  "
+Exec="/games/My Game/game.exe"
+Cover='/art/my game.jpg'
+PreLaunch=notify-send "starting"
+
+[Env]
+WINEDLLOVERRIDES="dxgi=n,b"
+PROTON_LOG='1'
+"#;
+        let card = Card::parse(content).unwrap();
+        assert_eq!(card.name, "  Spaced \"Out\"  ");
+        assert_eq!(card.exec, "/games/My Game/game.exe");
+        assert_eq!(card.artwork.cover, "/art/my game.jpg");
+        assert!(card.artwork.hero.is_empty());
+        assert_eq!(card.pre_launch.as_deref(), Some("notify-send \"starting\""));
+        assert_eq!(card.env, [("WINEDLLOVERRIDES".to_string(), "dxgi=n,b".to_string()), ("PROTON_LOG".to_string(), "1".to_string())]);
+
+        // An app's Exec goes to the shell untouched
+        let card = Card::parse("[App]\nName=Run\nExec=\"/opt/my app/run.sh\" --full\n").unwrap();
+        assert_eq!(card.exec, "\"/opt/my app/run.sh\" --full");
+        assert_eq!(unquote(r#""C:\Games\x.exe""#).unwrap(), r"C:\Games\x.exe");
+    }
+
+    #[test]
+    fn test_errors_have_line_numbers() {
+        let err = |content: &str| Card::parse(content).unwrap_err();
+        assert_eq!(err("[App]\nName=X\nExec\n").to_string(), "line 1: [App] has no Exec");
+        assert_eq!(err("Name=X\n[App]\n").to_string(), "line 2: [App] has no Name");
+        assert_eq!(err("\n[Game]\nExec=/g.exe\n").to_string(), "line 2: [Game] has no Name");
+        assert_eq!(err("[Env]\nA=1\n").line, None);
+    }
+
+    #[test]
+    fn test_skipped_lines() {
+        let content = "Version=2\n[App]\nName=Loose\nstray text\n=orphan\nExec=loose\n";
+        let doc = IniDocument::parse(content);
+        let lines: Vec<_> = doc.skipped().iter().map(|e| e.line).collect();
+        assert_eq!(lines, [Some(1), Some(4), Some(5)]);
+        assert_eq!(doc.to_string(), content);
+        let card = Card::from_document(&doc).unwrap();
+        assert_eq!((card.name.as_str(), card.exec.as_str(), card.skipped.len()), ("Loose", "loose", 3));
+    }
+
+    #[test]
+    fn test_broken_quotes_are_kept() {
+        let content = "[Game]\nName=\"open\nExec=\"/g.exe\" -x\n[Env]\nA='1\nB=2\n";
+        let card = Card::parse(content).unwrap();
+        assert_eq!((card.name.as_str(), card.exec.as_str()), ("\"open", "\"/g.exe\" -x"));
+        assert_eq!(card.env[0], ("A".to_string(), "'1".to_string()));
+        let lines: Vec<_> = card.skipped.iter().map(|e| e.line).collect();
+        assert_eq!(lines, [Some(2), Some(3), Some(5)]);
+        assert_eq!(card.skipped[0].to_string(), "line 2: quotes of Name: missing closing quote, keeping the value as written");
+    }
+
+    #[test]
+    fn test_quote_round_trip() {
+        for value in ["'Splosion Man", "\"Quoted\" Game", "C:\\Games\\x.exe", "  padded ", "tab\there", "plain name"] {
+            assert_eq!(unquote(&quote(value)).unwrap(), value);
+        }
+        assert_eq!(quote("plain name"), "plain name");
+        assert_eq!(quote("'Splosion Man"), "\"'Splosion Man\"");
+    }
+
+    #[test]
+    fn test_writes_keep_layout() {
+        let content = "# my game\n[Game]\n  Name=Old\n  Exec=/games/old.exe\n; hooks\nPreLaunch=echo hi\n\n[Env]\nDXVK_HUD=1\n";
+        let mut doc = IniDocument::parse(content);
+        assert_eq!(doc.to_string(), content);
+
+        doc.set("game", "Name", "New");
+        doc.set("Game", "Icon", "/icons/new.png");
+        doc.remove("Game", "PreLaunch");
+        doc.set("Env", "DXVK_HUD", "fps");
+        doc.set("Extra", "Key", "1");
+        assert_eq!(
+            doc.to_string(),
+            "# my game\n[Game]\n  Name=New\n  Exec=/games/old.exe\n; hooks\nIcon=/icons/new.png\n\n[Env]\nDXVK_HUD=fps\n\n[Extra]\nKey=1\n"
+        );
+    }
+}
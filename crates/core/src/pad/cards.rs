@@ -0,0 +1,382 @@
+//! Creating, editing and deleting the `.ini` cards under `~/.jolly/app`.
+//!
+//! Updates rewrite only the keys a card editor owns (`Name`, `Icon`, `Exec` and
+//! the `[App]`/`[Game]` section), so `[Env]`, hooks and comments written by hand
+//! survive an edit. The format itself lives in [`super::card_format`].
+
+use anyhow::{bail, Context, Result};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use super::card_format::{card_section, quote, Card, IniDocument};
+use super::desktop;
+use super::library::Library;
+use crate::{files, tr};
+
+/// The user-editable part of a card.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CardSpec {
+    pub app_id: String,
+    pub name: String,
+    /// Command line for apps, path to the Windows executable for games.
+    pub exec: String,
+    /// Icon name or absolute path; empty for none.
+    pub icon: String,
+    pub is_game: bool,
+}
+
+/// Why [`validate`] rejected a card. Apps show these to the user, so they are
+/// kept apart from I/O errors and displayed in the UI language.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CardError {
+    InvalidId(String),
+    DuplicateId(String),
+    MissingName,
+    MultilineField,
+    GameNotFound(String),
+    ProgramNotFound(String),
+    IconNotFound(String),
+}
+
+impl fmt::Display for CardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CardError::InvalidId(id) => f.write_str(&tr!("Invalid card id '{}': use letters, digits, '.', '_' or '-'", id)),
+            CardError::DuplicateId(id) => f.write_str(&tr!("A card with id '{}' already exists", id)),
+            CardError::MissingName => f.write_str(&tr!("The card needs a name")),
+            CardError::MultilineField => f.write_str(&tr!("Card fields cannot span several lines")),
+            CardError::GameNotFound(path) => f.write_str(&tr!("Game executable not found: {}", path)),
+            CardError::ProgramNotFound(exec) => f.write_str(&tr!("Program not found: {}", exec)),
+            CardError::IconNotFound(path) => f.write_str(&tr!("Icon file not found: {}", path)),
+        }
+    }
+}
+
+impl std::error::Error for CardError {}
+
+pub fn cards_dir() -> Result<PathBuf> {
+    let home = dirs::home_dir().context("Could not find home directory")?;
+    Ok(home.join(".jolly").join("app"))
+}
+
+fn card_path(app_id: &str) -> Result<PathBuf> {
+    Ok(cards_dir()?.join(format!("{}.ini", app_id)))
+}
+
+pub fn card_exists(app_id: &str) -> bool {
+    card_path(app_id).is_ok_and(|p| p.exists())
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+fn is_valid_app_id(app_id: &str) -> bool {
+    !app_id.is_empty()
+        && !app_id.starts_with('.')
+        && app_id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+/// An unused app_id derived from `name`, e.g. `"Super Game 2"` -> `super-game-2`.
+pub fn suggest_app_id(name: &str) -> String {
+    let mut base = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            base.push(c.to_ascii_lowercase());
+        } else if !base.is_empty() && !base.ends_with('-') {
+            base.push('-');
+        }
+    }
+    let base = base.trim_end_matches('-');
+    let base = if base.is_empty() { "card" } else { base };
+    let mut app_id = base.to_string();
+    let mut n = 2;
+    while card_exists(&app_id) {
+        app_id = format!("{}-{}", base, n);
+        n += 1;
+    }
+    app_id
+}
+
+/// Whether the program a command line starts with can be found.
+fn command_exists(exec: &str) -> bool {
+    let Some(program) = shell_words::split(exec).ok().and_then(|args| args.into_iter().next()) else {
+        return false;
+    };
+    if program.contains('/') {
+        return expand_home(&program).is_file();
+    }
+    env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).any(|dir| dir.join(&program).is_file()))
+        .unwrap_or(false)
+}
+
+/// Check a card before writing it. `editing` is the app_id of the card being
+/// updated, which may keep its own id.
+pub fn validate(spec: &CardSpec, editing: Option<&str>) -> Result<(), CardError> {
+    if !is_valid_app_id(&spec.app_id) {
+        return Err(CardError::InvalidId(spec.app_id.clone()));
+    }
+    if editing != Some(spec.app_id.as_str()) && card_exists(&spec.app_id) {
+        return Err(CardError::DuplicateId(spec.app_id.clone()));
+    }
+    if spec.name.trim().is_empty() {
+        return Err(CardError::MissingName);
+    }
+    if spec.name.contains('\n') || spec.exec.contains('\n') || spec.icon.contains('\n') {
+        return Err(CardError::MultilineField);
+    }
+    if spec.is_game {
+        if !expand_home(spec.exec.trim()).is_file() {
+            return Err(CardError::GameNotFound(spec.exec.clone()));
+        }
+    } else if !command_exists(&spec.exec) {
+        return Err(CardError::ProgramNotFound(spec.exec.clone()));
+    }
+    let icon = spec.icon.trim();
+    if (icon.contains('/') || icon.starts_with('~')) && !expand_home(icon).is_file() {
+        return Err(CardError::IconNotFound(icon.to_string()));
+    }
+    Ok(())
+}
+
+/// Read the editable fields of an existing card.
+pub fn load_card(app_id: &str) -> Result<CardSpec> {
+    let card = Card::load(&card_path(app_id)?)?;
+    Ok(CardSpec { app_id: app_id.to_string(), name: card.name, exec: card.exec, icon: card.icon, is_game: card.is_game })
+}
+
+fn render_new(spec: &CardSpec) -> String {
+    let section = if spec.is_game { "Game" } else { "App" };
+    let mut out = format!("[{}]\nName={}\n", section, quote(spec.name.trim()));
+    if !spec.icon.trim().is_empty() {
+        out.push_str(&format!("Icon={}\n", quote(spec.icon.trim())));
+    }
+    out.push_str(&format!("Exec={}\n", exec_value(spec)));
+    out
+}
+
+/// A game's Exec is a quotable path, an app's a command line kept as written.
+fn exec_value(spec: &CardSpec) -> String {
+    if spec.is_game { quote(spec.exec.trim()) } else { spec.exec.trim().to_string() }
+}
+
+/// Apply `spec` to an existing card file, keeping unrelated lines untouched.
+fn render_update(content: &str, spec: &CardSpec) -> String {
+    let mut doc = IniDocument::parse(content);
+    let Some(current) = card_section(&doc).map(str::to_string) else {
+        return render_new(spec) + &doc.to_string();
+    };
+    let section = if spec.is_game { "Game" } else { "App" };
+    doc.rename_section(&current, section);
+    doc.set(section, "Name", &quote(spec.name.trim()));
+    if spec.icon.trim().is_empty() {
+        doc.remove(section, "Icon");
+    } else {
+        doc.set(section, "Icon", &quote(spec.icon.trim()));
+    }
+    doc.set(section, "Exec", &exec_value(spec));
+    // The section header carries the type now
+    doc.remove(section, "Type");
+    doc.to_string()
+}
+
+/// Replace an existing card in one step.
+fn write_card(path: &Path, content: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    }
+    let tmp = path.with_extension("ini.tmp");
+    fs::write(&tmp, content).with_context(|| format!("Failed to write {:?}", tmp))?;
+    fs::rename(&tmp, path).with_context(|| format!("Failed to replace {:?}", path))?;
+    Ok(())
+}
+
+/// Write a new card. A card with the same id that appeared since
+/// [`validate`] is a [`CardError::DuplicateId`], never overwritten.
+fn write_new_card(path: &Path, app_id: &str, content: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    }
+    let mut file = match fs::OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return Err(CardError::DuplicateId(app_id.to_string()).into()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to create {:?}", path)),
+    };
+    if let Err(e) = file.write_all(content.as_bytes()) {
+        // Leave no half-written card behind
+        let _ = fs::remove_file(path);
+        return Err(e).with_context(|| format!("Failed to write {:?}", path));
+    }
+    Ok(())
+}
+
+pub fn create_card(spec: &CardSpec) -> Result<PathBuf> {
+    validate(spec, None)?;
+    let path = card_path(&spec.app_id)?;
+    write_new_card(&path, &spec.app_id, &render_new(spec))?;
+    Ok(path)
+}
+
+/// Update the card `app_id`. The id itself cannot change, so playtime and
+/// favorites stay attached to the card.
+pub fn update_card(app_id: &str, spec: &CardSpec) -> Result<PathBuf> {
+    if spec.app_id != app_id {
+        bail!("Cannot change the id of card '{}'", app_id);
+    }
+    validate(spec, Some(app_id))?;
+    let path = card_path(app_id)?;
+    let content = fs::read_to_string(&path).with_context(|| format!("Card '{}' does not exist", app_id))?;
+    let updated = render_update(&content, spec);
+    write_card(&path, &updated)?;
+    Ok(path)
+}
+
+/// Delete the card `app_id` and its library entry, so favorites, playtime and
+/// collections do not pass to a later card with the same id. The library goes
+/// first: if it cannot be updated, the card stays.
+pub fn delete_card(app_id: &str) -> Result<()> {
+    if !is_valid_app_id(app_id) {
+        return Err(CardError::InvalidId(app_id.to_string()).into());
+    }
+    let path = card_path(app_id)?;
+    if !path.exists() {
+        bail!("Card '{}' does not exist", app_id);
+    }
+    Library::update(|library| library.forget(app_id))?;
+    fs::remove_file(&path).with_context(|| format!("Failed to delete card {:?}", path))
+}
+
+/// Starting points for a new card: installed applications plus programs and
+/// Windows executables found in `~/Games`.
+pub fn card_suggestions() -> Vec<CardSpec> {
+    let mut suggestions = Vec::new();
+    if let Some(games) = dirs::home_dir().map(|h| h.join("Games")) {
+        collect_programs(&games, 3, &mut suggestions);
+    }
+    suggestions.sort_by_cached_key(|s| s.name.to_lowercase());
+    for app in desktop::scan_desktop_entries() {
+        suggestions.push(CardSpec {
+            app_id: String::new(),
+            name: app.name,
+            exec: app.exec,
+            icon: app.icon,
+            // Native commands, even in the Game category: game cards go through
+            // game-launcher and Proton, which only suit Windows programs
+            is_game: false,
+        });
+    }
+    suggestions
+}
+
+fn collect_programs(dir: &Path, depth: u32, out: &mut Vec<CardSpec>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if depth > 0 {
+                collect_programs(&path, depth - 1, out);
+            }
+            continue;
+        }
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else { continue };
+        if !files::is_program(&path) {
+            continue;
+        }
+        // Installers and uninstallers are never what people want on a card
+        let lower = stem.to_ascii_lowercase();
+        if ["unins", "setup", "vcredist", "dxsetup", "crashhandler"].iter().any(|p| lower.starts_with(p)) {
+            continue;
+        }
+        out.push(spec_for_program(&path));
+    }
+}
+
+/// A card for the program at `path`: Windows programs become games run through
+/// Proton, anything else is started directly.
+pub fn spec_for_program(path: &Path) -> CardSpec {
+    let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("").to_ascii_lowercase();
+    let is_game = matches!(ext.as_str(), "exe" | "msi" | "bat");
+    let path_str = path.to_string_lossy().into_owned();
+    CardSpec {
+        app_id: String::new(),
+        name: path.file_stem().map(|s| s.to_string_lossy().replace(['_', '.'], " ")).unwrap_or_default(),
+        // game-launcher wants the bare path, apps a command line
+        exec: if is_game { path_str } else { shell_words::quote(&path_str).into_owned() },
+        icon: String::new(),
+        is_game,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_update_keeps_other_sections() {
+        let content = "# my game\n[Game]\nName=Old\nExec=/games/old.exe\nPreLaunch=echo hi\n\n[Env]\nDXVK_HUD=1\n";
+        let spec = CardSpec {
+            app_id: "old".into(),
+            name: "New".into(),
+            exec: "/games/new.exe".into(),
+            icon: "/icons/new.png".into(),
+            is_game: true,
+        };
+        assert_eq!(
+            render_update(content, &spec),
+            "# my game\n[Game]\nName=New\nExec=/games/new.exe\nPreLaunch=echo hi\nIcon=/icons/new.png\n\n[Env]\nDXVK_HUD=1\n"
+        );
+
+        let app = CardSpec { is_game: false, icon: String::new(), exec: "foot".into(), ..spec };
+        assert_eq!(render_update("[App]\nName=Old\nIcon=x\nType=Game\nExec=a\n", &app), "[App]\nName=New\nExec=foot\n");
+    }
+
+    #[test]
+    fn test_render_quotes_names() {
+        for name in ["'Splosion Man", "\"Quoted\" Game"] {
+            let spec = CardSpec {
+                app_id: "quoted".into(),
+                name: name.into(),
+                exec: "/games/My Game/game.exe".into(),
+                icon: String::new(),
+                is_game: true,
+            };
+            let card = Card::parse(&render_new(&spec)).unwrap();
+            assert_eq!((card.name.as_str(), card.exec.as_str()), (name, "/games/My Game/game.exe"));
+            let updated = render_update("[Game]\nName=Old\nExec=/games/old.exe\n", &spec);
+            assert_eq!(Card::parse(&updated).unwrap().name, name);
+        }
+    }
+
+    #[test]
+    fn test_app_id_validation() {
+        assert!(is_valid_app_id("org.example.Game_2-x"));
+        assert!(!is_valid_app_id("../evil"));
+        assert!(!is_valid_app_id(".hidden"));
+        assert!(!is_valid_app_id(""));
+
+        let spec = CardSpec { app_id: "../evil".into(), name: "Evil".into(), ..Default::default() };
+        assert_eq!(validate(&spec, None), Err(CardError::InvalidId("../evil".into())));
+        let error = create_card(&spec).unwrap_err();
+        assert_eq!(error.downcast_ref::<CardError>(), Some(&CardError::InvalidId("../evil".into())));
+    }
+
+    #[test]
+    fn test_new_card_is_never_overwritten() {
+        let dir = env::temp_dir().join(format!("jollypad-cards-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("game.ini");
+        write_new_card(&path, "game", "[App]\nName=First\n").unwrap();
+        let error = write_new_card(&path, "game", "[App]\nName=Second\n").unwrap_err();
+        let content = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(error.downcast_ref::<CardError>(), Some(&CardError::DuplicateId("game".into())));
+        assert_eq!(content, "[App]\nName=First\n");
+    }
+}
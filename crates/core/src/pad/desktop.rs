@@ -0,0 +1,376 @@
+//! freedesktop.org desktop entry discovery.
+//!
+//! Scans `applications/` below `XDG_DATA_HOME`, `XDG_DATA_DIRS` and the Flatpak
+//! export directories, following the Desktop Entry Specification for lookup
+//! precedence, visibility keys and localized names.
+
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{AppItem, AppSource, Artwork};
+
+/// Desktop names we identify as in `OnlyShowIn`/`NotShowIn`.
+const DEFAULT_DESKTOP: &str = "JollyPad";
+
+/// `XDG_DATA_HOME`, `XDG_DATA_DIRS` and the Flatpak exports, most important first.
+pub(super) fn data_dirs() -> Vec<PathBuf> {
+    let home = dirs::home_dir();
+    let data_home = env::var("XDG_DATA_HOME")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|h| h.join(".local/share")));
+    let data_dirs = env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+
+    let mut bases: Vec<PathBuf> = data_home.into_iter().collect();
+    bases.extend(data_dirs.split(':').filter(|d| !d.is_empty()).map(PathBuf::from));
+    // Flatpak normally adds these to XDG_DATA_DIRS, but not when the session was
+    // started without its profile snippet
+    if let Some(home) = &home {
+        bases.push(home.join(".local/share/flatpak/exports/share"));
+    }
+    bases.push(PathBuf::from("/var/lib/flatpak/exports/share"));
+
+    let mut seen = HashSet::new();
+    bases.into_iter().filter(|d| seen.insert(d.clone())).collect()
+}
+
+/// `applications` directories in precedence order, most important first.
+fn application_dirs() -> Vec<PathBuf> {
+    data_dirs().into_iter().map(|b| b.join("applications")).collect()
+}
+
+/// Desktop file paths with their desktop file IDs (`sub/foo.desktop` -> `sub-foo.desktop`).
+/// Symlinked files are read, but symlinked directories are not entered, so a
+/// link back up the tree cannot send the scan round in circles.
+fn desktop_files(dir: &Path, prefix: &str, out: &mut Vec<(String, PathBuf)>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(name) = entry.file_name().into_string() else { continue };
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            desktop_files(&path, &format!("{}{}-", prefix, name), out);
+        } else if name.ends_with(".desktop") {
+            out.push((format!("{}{}", prefix, name), path));
+        }
+    }
+}
+
+/// Locale keys to try for `Name[...]`, most specific first, from `lang_COUNTRY.ENCODING@MODIFIER`.
+fn locale_variants(locale: &str) -> Vec<String> {
+    let (rest, modifier) = match locale.split_once('@') {
+        Some((rest, modifier)) => (rest, Some(modifier)),
+        None => (locale, None),
+    };
+    let rest = rest.split('.').next().unwrap_or(rest);
+    let (lang, country) = match rest.split_once('_') {
+        Some((lang, country)) => (lang, Some(country)),
+        None => (rest, None),
+    };
+    let mut variants = Vec::new();
+    if let (Some(country), Some(modifier)) = (country, modifier) {
+        variants.push(format!("{}_{}@{}", lang, country, modifier));
+    }
+    if let Some(country) = country {
+        variants.push(format!("{}_{}", lang, country));
+    }
+    if let Some(modifier) = modifier {
+        variants.push(format!("{}@{}", lang, modifier));
+    }
+    if !lang.is_empty() && lang != "C" && lang != "POSIX" {
+        variants.push(lang.to_string());
+    }
+    variants
+}
+
+fn current_locale() -> String {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|k| env::var(k).ok())
+        .find(|v| !v.is_empty())
+        .unwrap_or_default()
+}
+
+fn current_desktops() -> Vec<String> {
+    env::var("XDG_CURRENT_DESKTOP")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| DEFAULT_DESKTOP.to_string())
+        .split(':')
+        .map(str::to_string)
+        .collect()
+}
+
+/// Unescape `\s`, `\n`, `\t`, `\r` and `\\` in a string value.
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => out.push(' '),
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+fn list(value: &str) -> Vec<&str> {
+    value.split(';').map(str::trim).filter(|s| !s.is_empty()).collect()
+}
+
+/// Expand the field codes of an `Exec` value. File and URL arguments are dropped
+/// since cards are launched without any.
+pub fn strip_field_codes(exec: &str, name: &str, icon: &str, path: &Path) -> Option<String> {
+    let args = shell_words::split(exec).ok()?;
+    let mut out = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "%f" | "%F" | "%u" | "%U" | "%d" | "%D" | "%n" | "%N" | "%v" | "%m" => {}
+            "%i" => {
+                if !icon.is_empty() {
+                    out.push("--icon".to_string());
+                    out.push(icon.to_string());
+                }
+            }
+            "%c" => out.push(name.to_string()),
+            "%k" => out.push(path.to_string_lossy().into_owned()),
+            _ => {
+                // Field codes embedded in a larger argument
+                let mut expanded = String::new();
+                let mut chars = arg.chars();
+                while let Some(c) = chars.next() {
+                    if c != '%' {
+                        expanded.push(c);
+                        continue;
+                    }
+                    match chars.next() {
+                        Some('%') => expanded.push('%'),
+                        Some('c') => expanded.push_str(name),
+                        Some('k') => expanded.push_str(&path.to_string_lossy()),
+                        Some(_) | None => {}
+                    }
+                }
+                out.push(expanded);
+            }
+        }
+    }
+    if out.is_empty() {
+        return None;
+    }
+    Some(shell_words::join(out))
+}
+
+/// Outcome of parsing one desktop file.
+#[derive(Debug, PartialEq)]
+pub enum DesktopEntry {
+    /// A launchable application.
+    App(Box<AppItem>),
+    /// `Hidden=true`: the ID is deleted and masks entries with lower precedence.
+    Hidden,
+    /// Not shown in this environment (`NoDisplay`, `OnlyShowIn`, not an application, ...).
+    Skipped,
+}
+
+pub fn parse_desktop_entry(content: &str, desktop_id: &str, path: &Path, locale: &str, desktops: &[String]) -> DesktopEntry {
+    let variants = locale_variants(locale);
+    let mut in_entry = false;
+    let mut name = String::new();
+    // Index into `variants` of the best localized name found so far
+    let mut name_rank = usize::MAX;
+    let mut localized_name = None;
+    let mut icon = String::new();
+    let mut exec = String::new();
+    let mut entry_type = String::new();
+    let mut categories = Vec::new();
+    let mut no_display = false;
+    let mut hidden = false;
+    let mut terminal = false;
+    let mut only_show_in: Option<Vec<String>> = None;
+    let mut not_show_in = Vec::new();
+    let mut try_exec = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else { continue };
+        let key = key.trim();
+        let value = value.trim();
+        if let Some(lang) = key.strip_prefix("Name[").and_then(|k| k.strip_suffix(']')) {
+            if let Some(rank) = variants.iter().position(|v| v == lang) {
+                if rank < name_rank {
+                    name_rank = rank;
+                    localized_name = Some(unescape(value));
+                }
+            }
+            continue;
+        }
+        match key {
+            "Type" => entry_type = value.to_string(),
+            "Name" => name = unescape(value),
+            "Icon" => icon = unescape(value),
+            "Exec" => exec = unescape(value),
+            "TryExec" => try_exec = Some(unescape(value)),
+            "Categories" => categories = list(value).into_iter().map(str::to_string).collect(),
+            "NoDisplay" => no_display = value == "true",
+            "Hidden" => hidden = value == "true",
+            "Terminal" => terminal = value == "true",
+            "OnlyShowIn" => only_show_in = Some(list(value).into_iter().map(str::to_string).collect()),
+            "NotShowIn" => not_show_in = list(value).into_iter().map(str::to_string).collect(),
+            _ => {}
+        }
+    }
+
+    if hidden {
+        return DesktopEntry::Hidden;
+    }
+    // No terminal emulator on the pad, so terminal apps cannot be shown
+    if entry_type != "Application" || no_display || terminal || name.is_empty() {
+        return DesktopEntry::Skipped;
+    }
+    let shown_here = |list: &[String]| list.iter().any(|d| desktops.iter().any(|c| c.eq_ignore_ascii_case(d)));
+    if only_show_in.as_deref().is_some_and(|l| !shown_here(l)) || shown_here(&not_show_in) {
+        return DesktopEntry::Skipped;
+    }
+    if try_exec.as_deref().is_some_and(|t| !program_exists(t)) {
+        return DesktopEntry::Skipped;
+    }
+
+    let name = localized_name.unwrap_or(name);
+    let Some(exec) = strip_field_codes(&exec, &name, &icon, path) else { return DesktopEntry::Skipped };
+    let category = if categories.iter().any(|c| c == "Game") {
+        "Game"
+    } else if categories.iter().any(|c| c == "AudioVideo" || c == "Audio" || c == "Video") {
+        "Media"
+    } else {
+        "App"
+    };
+
+    DesktopEntry::App(Box::new(AppItem {
+        name,
+        icon,
+        exec,
+        // Wayland clients use the desktop file ID as app_id, which lets home focus
+        // an already running window
+        app_id: desktop_id.trim_end_matches(".desktop").to_string(),
+        category: category.to_string(),
+        source: AppSource::Desktop,
+        artwork: Artwork::default(),
+    }))
+}
+
+fn program_exists(program: &str) -> bool {
+    if program.contains('/') {
+        return Path::new(program).is_file();
+    }
+    env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+/// Cards for every visible installed application.
+pub fn scan_desktop_entries() -> Vec<AppItem> {
+    let locale = current_locale();
+    let desktops = current_desktops();
+    let mut seen_ids = HashSet::new();
+    let mut apps = Vec::new();
+    for dir in application_dirs() {
+        let mut files = Vec::new();
+        desktop_files(&dir, "", &mut files);
+        files.sort();
+        for (desktop_id, path) in files {
+            // The first directory defining an ID wins, even if it hides the entry
+            if !seen_ids.insert(desktop_id.clone()) {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(&path) else { continue };
+            if let DesktopEntry::App(app) = parse_desktop_entry(&content, &desktop_id, &path, &locale, &desktops) {
+                apps.push(*app);
+            }
+        }
+    }
+    apps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str, locale: &str, desktops: &[&str]) -> DesktopEntry {
+        let desktops: Vec<String> = desktops.iter().map(|d| d.to_string()).collect();
+        parse_desktop_entry(content, "org.example.Player.desktop", Path::new("/x/org.example.Player.desktop"), locale, &desktops)
+    }
+
+    #[test]
+    fn test_parse_desktop_entry() {
+        let content = "[Desktop Entry]\nType=Application\nName=Player\nName[zh]=播放器\nName[zh_CN]=媒体播放器\n\
+                       Icon=org.example.Player\nExec=player --new-window %U\nCategories=AudioVideo;Player;\n\
+                       [Desktop Action Quit]\nName=Quit\nExec=player --quit\n";
+        let DesktopEntry::App(app) = parse(content, "zh_CN.UTF-8", &["JollyPad"]) else { panic!("not an app") };
+        assert_eq!(app.name, "媒体播放器");
+        assert_eq!(app.exec, "player --new-window");
+        assert_eq!(app.app_id, "org.example.Player");
+        assert_eq!(app.category, "Media");
+
+        let DesktopEntry::App(app) = parse(content, "de_DE", &["JollyPad"]) else { panic!("not an app") };
+        assert_eq!(app.name, "Player");
+    }
+
+    #[test]
+    fn test_visibility_keys() {
+        let base = "[Desktop Entry]\nType=Application\nName=Tool\nExec=tool\n";
+        assert_eq!(parse(&format!("{}NoDisplay=true\n", base), "", &["JollyPad"]), DesktopEntry::Skipped);
+        assert_eq!(parse(&format!("{}Hidden=true\n", base), "", &["JollyPad"]), DesktopEntry::Hidden);
+        assert_eq!(parse(&format!("{}OnlyShowIn=GNOME;KDE;\n", base), "", &["JollyPad"]), DesktopEntry::Skipped);
+        assert!(matches!(parse(&format!("{}OnlyShowIn=GNOME;\n", base), "", &["ubuntu", "GNOME"]), DesktopEntry::App(_)));
+        assert_eq!(parse(&format!("{}NotShowIn=JollyPad;\n", base), "", &["JollyPad"]), DesktopEntry::Skipped);
+    }
+
+    #[test]
+    fn test_desktop_files_skip_symlinked_dirs() {
+        let dir = env::temp_dir().join(format!("jollypad-desktop-{}", std::process::id()));
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("sub/a.desktop"), "").unwrap();
+        std::os::unix::fs::symlink(&dir, dir.join("sub/loop")).unwrap();
+        std::os::unix::fs::symlink(dir.join("sub/a.desktop"), dir.join("b.desktop")).unwrap();
+
+        let mut files = Vec::new();
+        desktop_files(&dir, "", &mut files);
+        fs::remove_dir_all(&dir).unwrap();
+        let mut ids: Vec<&str> = files.iter().map(|(id, _)| id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, ["b.desktop", "sub-a.desktop"]);
+    }
+
+    #[test]
+    fn test_strip_field_codes() {
+        let path = Path::new("/apps/a.desktop");
+        assert_eq!(strip_field_codes("app %F", "A", "a", path).as_deref(), Some("app"));
+        assert_eq!(strip_field_codes("app %i --name=%c 100%%", "My App", "a", path).as_deref(), Some("app --icon a '--name=My App' '100%'"));
+        assert_eq!(
+            strip_field_codes("/usr/bin/flatpak run --command=foo org.Foo @@u %U @@", "Foo", "", path).as_deref(),
+            Some("/usr/bin/flatpak run '--command=foo' org.Foo @@u @@")
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! Heroic Games Launcher import.
+//!
+//! Reads the installed Epic (legendary) and GOG games from Heroic's config
+//! directory, native or Flatpak, and launches them through `heroic://launch`.
+//!
+//! Heroic starts the game itself, outside the launch wrapper, so these cards get
+//! no session, playtime, hooks or launch log (see [`crate::session`]), only a
+//! launch count.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{AppItem, AppSource, Artwork};
+
+#[derive(Debug, Clone)]
+struct HeroicConfig {
+    path: PathBuf,
+    flatpak: bool,
+}
+
+fn heroic_configs() -> Vec<HeroicConfig> {
+    let Some(home) = dirs::home_dir() else { return Vec::new() };
+    let config = dirs::config_dir().unwrap_or_else(|| home.join(".config"));
+    [
+        (config.join("heroic"), false),
+        (home.join(".var/app/com.heroicgameslauncher.hgl/config/heroic"), true),
+    ]
+    .into_iter()
+    .filter(|(path, _)| path.is_dir())
+    .map(|(path, flatpak)| HeroicConfig { path, flatpak })
+    .collect()
+}
+
+/// An installed game as `(runner, app_name, title)`.
+#[derive(Debug, PartialEq)]
+pub struct HeroicGame {
+    pub runner: &'static str,
+    pub app_name: String,
+    pub title: String,
+}
+
+#[derive(Deserialize)]
+struct LegendaryInstall {
+    app_name: String,
+    title: String,
+    #[serde(default)]
+    is_dlc: bool,
+}
+
+/// Parse legendary's `installed.json`, a map of app name to install info.
+pub fn parse_legendary_installed(content: &str) -> Vec<HeroicGame> {
+    let installed: HashMap<String, LegendaryInstall> = match serde_json::from_str(content) {
+        Ok(installed) => installed,
+        Err(e) => {
+            eprintln!("Failed to parse legendary installed.json: {}", e);
+            return Vec::new();
+        }
+    };
+    installed
+        .into_values()
+        .filter(|game| !game.is_dlc)
+        .map(|game| HeroicGame { runner: "legendary", app_name: game.app_name, title: game.title })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct GogInstalled {
+    #[serde(default)]
+    installed: Vec<GogInstall>,
+}
+
+#[derive(Deserialize)]
+struct GogInstall {
+    #[serde(rename = "appName")]
+    app_name: String,
+    #[serde(default)]
+    is_dlc: bool,
+}
+
+#[derive(Deserialize)]
+struct GogLibrary {
+    #[serde(default)]
+    games: Vec<GogLibraryGame>,
+}
+
+#[derive(Deserialize)]
+struct GogLibraryGame {
+    app_name: String,
+    title: String,
+}
+
+/// Parse GOG's `installed.json`, taking titles from the cached `library.json`.
+pub fn parse_gog_installed(installed: &str, library: Option<&str>) -> Vec<HeroicGame> {
+    let installed: GogInstalled = match serde_json::from_str(installed) {
+        Ok(installed) => installed,
+        Err(e) => {
+            eprintln!("Failed to parse GOG installed.json: {}", e);
+            return Vec::new();
+        }
+    };
+    let titles: HashMap<String, String> = library
+        .and_then(|content| serde_json::from_str::<GogLibrary>(content).ok())
+        .map(|library| library.games.into_iter().map(|g| (g.app_name, g.title)).collect())
+        .unwrap_or_default();
+    installed
+        .installed
+        .into_iter()
+        .filter(|game| !game.is_dlc)
+        .map(|game| {
+            let title = titles.get(&game.app_name).cloned().unwrap_or_else(|| game.app_name.clone());
+            HeroicGame { runner: "gog", app_name: game.app_name, title }
+        })
+        .collect()
+}
+
+/// Icons Heroic writes for its own desktop shortcuts.
+fn find_icon(config: &Path, app_name: &str) -> Option<PathBuf> {
+    ["png", "jpg"]
+        .iter()
+        .map(|ext| config.join("icons").join(format!("{}.{}", app_name, ext)))
+        .find(|p| p.is_file())
+}
+
+/// Cards for every installed Heroic game.
+pub fn import_heroic_games() -> Vec<AppItem> {
+    let mut apps: Vec<AppItem> = Vec::new();
+    for config in heroic_configs() {
+        let mut games = Vec::new();
+        if let Ok(content) = fs::read_to_string(config.path.join("legendaryConfig/legendary/installed.json")) {
+            games.extend(parse_legendary_installed(&content));
+        }
+        if let Ok(content) = fs::read_to_string(config.path.join("gog_store/installed.json")) {
+            let library = fs::read_to_string(config.path.join("gog_store/library.json")).ok();
+            games.extend(parse_gog_installed(&content, library.as_deref()));
+        }
+
+        let heroic = if config.flatpak { "flatpak run com.heroicgameslauncher.hgl" } else { "heroic" };
+        for game in games {
+            let app_id = format!("heroic_{}_{}", game.runner, game.app_name);
+            if apps.iter().any(|a| a.app_id == app_id) {
+                continue;
+            }
+            let icon = find_icon(&config.path, &game.app_name)
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "heroic".to_string());
+            apps.push(AppItem {
+                name: game.title,
+                icon,
+                exec: format!("{} heroic://launch/{}/{}", heroic, game.runner, game.app_name),
+                app_id,
+                category: "Game".to_string(),
+                source: AppSource::Heroic,
+                artwork: Artwork::default(),
+            });
+        }
+    }
+    apps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_heroic_installed() {
+        let legendary = r#"{
+            "Quail": {"app_name": "Quail", "title": "Rocket League", "install_path": "/games/rl", "is_dlc": false},
+            "QuailDLC": {"app_name": "QuailDLC", "title": "Season Pass", "is_dlc": true}
+        }"#;
+        assert_eq!(
+            parse_legendary_installed(legendary),
+            [HeroicGame { runner: "legendary", app_name: "Quail".into(), title: "Rocket League".into() }]
+        );
+
+        let gog = r#"{"installed": [
+            {"platform": "linux", "appName": "1207658924", "install_path": "/games/ut"},
+            {"platform": "windows", "appName": "1400000000"}
+        ]}"#;
+        let library = r#"{"games": [{"app_name": "1207658924", "title": "Unreal Tournament 2004"}]}"#;
+        let games = parse_gog_installed(gog, Some(library));
+        assert_eq!(games[0].title, "Unreal Tournament 2004");
+        // Falls back to the app name when the library cache has no entry
+        assert_eq!(games[1].title, "1400000000");
+    }
+}
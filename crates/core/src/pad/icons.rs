@@ -0,0 +1,449 @@
+//! freedesktop.org icon theme lookup.
+//!
+//! Follows the Icon Theme Specification: the user's theme and its `Inherits=`
+//! chain, then hicolor, picking the `index.theme` directory that best matches
+//! the requested size and scale, then unthemed icons and `/usr/share/pixmaps`.
+//! JollyPad's own icon directories override every theme.
+//!
+//! Scanning a theme like Papirus reads thousands of files, so the index is kept
+//! in `~/.cache/jollypad/icon-index.json` and only rebuilt when the theme
+//! changes or one of the scanned directories has a different mtime.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use super::card_format::IniDocument;
+use super::desktop;
+
+/// Bumped whenever the cached layout changes.
+const INDEX_VERSION: u32 = 1;
+/// In order of preference within one directory.
+const EXTENSIONS: [&str; 3] = ["png", "svg", "xpm"];
+/// Size used by [`IconLoader::icon_path`], large enough for pad cards.
+pub const DEFAULT_ICON_SIZE: u32 = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum DirType {
+    Fixed,
+    Scalable,
+    Threshold,
+}
+
+/// One `Directories=` entry of a theme, below one base directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ThemeDir {
+    path: PathBuf,
+    size: u32,
+    scale: u32,
+    kind: DirType,
+    min_size: u32,
+    max_size: u32,
+    threshold: u32,
+}
+
+impl ThemeDir {
+    fn from_index(path: PathBuf, index: &IniDocument, section: &str) -> Option<Self> {
+        let number = |key: &str| index.get(section, key).and_then(|v| v.parse::<u32>().ok());
+        let size = number("Size")?;
+        let kind = match index.get(section, "Type").unwrap_or("Threshold") {
+            "Fixed" => DirType::Fixed,
+            "Scalable" => DirType::Scalable,
+            _ => DirType::Threshold,
+        };
+        Some(ThemeDir {
+            path,
+            size,
+            scale: number("Scale").unwrap_or(1).max(1),
+            kind,
+            min_size: number("MinSize").unwrap_or(size),
+            max_size: number("MaxSize").unwrap_or(size),
+            threshold: number("Threshold").unwrap_or(2),
+        })
+    }
+
+    fn matches_size(&self, size: u32, scale: u32) -> bool {
+        if self.scale != scale {
+            return false;
+        }
+        match self.kind {
+            DirType::Fixed => self.size == size,
+            DirType::Scalable => (self.min_size..=self.max_size).contains(&size),
+            DirType::Threshold => (self.size.saturating_sub(self.threshold)..=self.size + self.threshold).contains(&size),
+        }
+    }
+
+    fn size_distance(&self, size: u32, scale: u32) -> u32 {
+        let wanted = size * scale;
+        let (min, max) = match self.kind {
+            DirType::Fixed => (self.size, self.size),
+            DirType::Scalable => (self.min_size, self.max_size),
+            DirType::Threshold => (self.size.saturating_sub(self.threshold), self.size + self.threshold),
+        };
+        let (min, max) = (min * self.scale, max * self.scale);
+        if wanted < min {
+            min - wanted
+        } else {
+            wanted.saturating_sub(max)
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ThemeIndex {
+    name: String,
+    dirs: Vec<ThemeDir>,
+    /// Icon name to `(directory, extension)` indices, in directory order
+    icons: HashMap<String, Vec<(u32, u8)>>,
+}
+
+impl ThemeIndex {
+    /// Exact size matches win; otherwise the directory closest in size.
+    fn lookup(&self, name: &str, size: u32, scale: u32) -> Option<PathBuf> {
+        let &(dir, ext) = self.icons.get(name)?.iter().min_by_key(|&&(dir, ext)| {
+            let d = &self.dirs[dir as usize];
+            (!d.matches_size(size, scale), d.size_distance(size, scale), dir, ext)
+        })?;
+        Some(self.dirs[dir as usize].path.join(format!("{}.{}", name, EXTENSIONS[ext as usize])))
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IconIndex {
+    version: u32,
+    theme: String,
+    override_dirs: Vec<PathBuf>,
+    /// Every directory and index.theme the index was built from, with its mtime
+    /// in milliseconds, `None` if it did not exist
+    sources: Vec<(PathBuf, Option<u64>)>,
+    overrides: HashMap<String, PathBuf>,
+    /// The theme, its parents and hicolor, in lookup order
+    themes: Vec<ThemeIndex>,
+    unthemed: HashMap<String, PathBuf>,
+}
+
+fn mtime(path: &Path) -> Option<u64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    modified.duration_since(UNIX_EPOCH).ok().map(|d| d.as_millis() as u64)
+}
+
+/// `name.ext` files with a known extension in `dir`, as `(name, extension)`.
+fn icon_files(dir: &Path) -> Vec<(String, u8)> {
+    let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+    let mut files: Vec<(String, u8)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().into_string().ok()?;
+            let (name, ext) = file_name.rsplit_once('.')?;
+            let ext = EXTENSIONS.iter().position(|e| *e == ext)?;
+            Some((name.to_string(), ext as u8))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn read_theme_index(name: &str, bases: &[PathBuf]) -> Option<(PathBuf, IniDocument)> {
+    bases.iter().map(|b| b.join(name).join("index.theme")).find_map(|path| {
+        let content = fs::read_to_string(&path).ok()?;
+        Some((path, IniDocument::parse(&content)))
+    })
+}
+
+/// The theme and its parents depth first, as the spec walks them, then hicolor.
+fn theme_chain(theme: &str, bases: &[PathBuf]) -> Vec<(String, PathBuf, IniDocument)> {
+    fn visit(name: &str, bases: &[PathBuf], chain: &mut Vec<(String, PathBuf, IniDocument)>) {
+        if name.is_empty() || chain.iter().any(|(n, _, _)| n == name) {
+            return;
+        }
+        let Some((path, doc)) = read_theme_index(name, bases) else { return };
+        let parents: Vec<String> = doc
+            .get("Icon Theme", "Inherits")
+            .unwrap_or_default()
+            .split(',')
+            .map(|p| p.trim().to_string())
+            .collect();
+        chain.push((name.to_string(), path, doc));
+        // hicolor always comes last, even when a theme names it as a parent
+        for parent in parents.iter().filter(|p| *p != "hicolor") {
+            visit(parent, bases, chain);
+        }
+    }
+    let mut chain = Vec::new();
+    visit(theme, bases, &mut chain);
+    visit("hicolor", bases, &mut chain);
+    chain
+}
+
+impl IconIndex {
+    fn build(theme: &str, bases: &[PathBuf], override_dirs: &[PathBuf], pixmap_dirs: &[PathBuf]) -> Self {
+        let mut index = IconIndex {
+            version: INDEX_VERSION,
+            theme: theme.to_string(),
+            override_dirs: override_dirs.to_vec(),
+            ..Default::default()
+        };
+        // Watching the bases catches themes being installed in a new place
+        for base in bases {
+            index.sources.push((base.clone(), mtime(base)));
+        }
+
+        for dir in override_dirs {
+            index.sources.push((dir.clone(), mtime(dir)));
+            for (name, ext) in icon_files(dir) {
+                index.overrides.entry(name.clone()).or_insert_with(|| dir.join(format!("{}.{}", name, EXTENSIONS[ext as usize])));
+            }
+        }
+
+        for (name, index_path, doc) in theme_chain(theme, bases) {
+            index.sources.push((index_path.clone(), mtime(&index_path)));
+            let mut subdirs: Vec<&str> = Vec::new();
+            for key in ["Directories", "ScaledDirectories"] {
+                for dir in doc.get("Icon Theme", key).unwrap_or_default().split(',').map(str::trim) {
+                    if !dir.is_empty() && !subdirs.contains(&dir) {
+                        subdirs.push(dir);
+                    }
+                }
+            }
+
+            let mut theme_index = ThemeIndex { name: name.clone(), ..Default::default() };
+            // A theme may be spread over several bases, e.g. hicolor
+            for base in bases {
+                let root = base.join(&name);
+                if !root.is_dir() {
+                    continue;
+                }
+                index.sources.push((root.clone(), mtime(&root)));
+                for subdir in &subdirs {
+                    let path = root.join(subdir);
+                    let Some(modified) = mtime(&path) else { continue };
+                    let Some(dir) = ThemeDir::from_index(path.clone(), &doc, subdir) else { continue };
+                    index.sources.push((path.clone(), Some(modified)));
+                    let dir_index = theme_index.dirs.len() as u32;
+                    for (icon, ext) in icon_files(&path) {
+                        theme_index.icons.entry(icon).or_default().push((dir_index, ext));
+                    }
+                    theme_index.dirs.push(dir);
+                }
+            }
+            index.themes.push(theme_index);
+        }
+
+        // Icons directly in a base directory, then pixmaps
+        for dir in bases.iter().chain(pixmap_dirs) {
+            if !bases.contains(dir) {
+                index.sources.push((dir.clone(), mtime(dir)));
+            }
+            for (name, ext) in icon_files(dir) {
+                index.unthemed.entry(name.clone()).or_insert_with(|| dir.join(format!("{}.{}", name, EXTENSIONS[ext as usize])));
+            }
+        }
+        index
+    }
+
+    fn is_fresh(&self, theme: &str, override_dirs: &[PathBuf]) -> bool {
+        self.version == INDEX_VERSION
+            && self.theme == theme
+            && self.override_dirs == override_dirs
+            && self.sources.iter().all(|(path, modified)| mtime(path) == *modified)
+    }
+
+    fn lookup(&self, name: &str, size: u32, scale: u32) -> Option<PathBuf> {
+        if let Some(path) = self.overrides.get(name) {
+            return Some(path.clone());
+        }
+        self.themes
+            .iter()
+            .find_map(|theme| theme.lookup(name, size, scale))
+            .or_else(|| self.unthemed.get(name).cloned())
+    }
+}
+
+/// Icon directories shipped with JollyPad, most specific first.
+fn jollypad_icon_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Ok(icon_dir) = env::var("JOLLYPAD_ICON_DIR") {
+        dirs.push(PathBuf::from(icon_dir));
+    }
+    if let Some(exe_dir) = env::current_exe().ok().and_then(|p| p.parent().map(Path::to_path_buf)) {
+        dirs.push(exe_dir.join("../share/jollypad/icons"));
+        dirs.push(exe_dir.join("icons"));
+    }
+    // Running from a checkout
+    if let Ok(cwd) = env::current_dir() {
+        dirs.push(cwd.join("assets/icons"));
+    }
+    dirs.push(PathBuf::from("/usr/share/jollypad/icons"));
+    dirs
+}
+
+/// Icon theme bases: `~/.icons`, then `icons/` below the XDG data dirs.
+fn base_dirs() -> Vec<PathBuf> {
+    let mut bases: Vec<PathBuf> = dirs::home_dir().map(|h| h.join(".icons")).into_iter().collect();
+    bases.extend(desktop::data_dirs().into_iter().map(|d| d.join("icons")));
+    bases
+}
+
+/// The configured icon theme: `JOLLYPAD_ICON_THEME`, the GTK setting, the KDE
+/// setting, else hicolor.
+pub fn current_theme() -> String {
+    if let Ok(theme) = env::var("JOLLYPAD_ICON_THEME") {
+        if !theme.is_empty() {
+            return theme;
+        }
+    }
+    let config = dirs::config_dir().unwrap_or_default();
+    let settings = [
+        (config.join("gtk-4.0/settings.ini"), "Settings", "gtk-icon-theme-name"),
+        (config.join("gtk-3.0/settings.ini"), "Settings", "gtk-icon-theme-name"),
+        (config.join("kdeglobals"), "Icons", "Theme"),
+    ];
+    for (path, section, key) in settings {
+        let Ok(content) = fs::read_to_string(&path) else { continue };
+        let doc = IniDocument::parse(&content);
+        if let Some(theme) = doc.get(section, key).filter(|t| !t.is_empty()) {
+            return theme.trim_matches('"').to_string();
+        }
+    }
+    "hicolor".to_string()
+}
+
+fn cache_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|d| d.join("jollypad").join("icon-index.json"))
+}
+
+fn save_index(path: &Path, index: &IconIndex) {
+    let result = (|| -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec(index)?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    })();
+    if let Err(e) = result {
+        eprintln!("Failed to write icon cache {:?}: {}", path, e);
+    }
+}
+
+/// Resolves icon names to files, shared by home, nav and settings.
+#[derive(Debug)]
+pub struct IconLoader {
+    index: IconIndex,
+}
+
+impl Default for IconLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IconLoader {
+    /// Load the cached index, rebuilding it when it is stale.
+    pub fn new() -> Self {
+        let theme = current_theme();
+        let override_dirs = jollypad_icon_dirs();
+        let cache = cache_path();
+        let cached = cache
+            .as_ref()
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|data| serde_json::from_slice::<IconIndex>(&data).ok())
+            .filter(|index| index.is_fresh(&theme, &override_dirs));
+        if let Some(index) = cached {
+            return Self { index };
+        }
+
+        let index = IconIndex::build(&theme, &base_dirs(), &override_dirs, &[PathBuf::from("/usr/share/pixmaps")]);
+        if let Some(path) = cache {
+            save_index(&path, &index);
+        }
+        Self { index }
+    }
+
+    /// Name of the icon theme in use.
+    pub fn theme(&self) -> &str {
+        &self.index.theme
+    }
+
+    /// The file for `icon` at `size` pixels and `scale`. Paths are returned as
+    /// they are when the file exists; a file extension on a name is ignored,
+    /// as some desktop entries carry one.
+    pub fn lookup(&self, icon: &str, size: u32, scale: u32) -> Option<PathBuf> {
+        let icon = icon.trim().trim_matches('\u{FEFF}');
+        if icon.is_empty() {
+            return None;
+        }
+        if icon.contains('/') {
+            let path = match (icon.strip_prefix("~/"), dirs::home_dir()) {
+                (Some(rest), Some(home)) => home.join(rest),
+                _ => PathBuf::from(icon),
+            };
+            return path.is_file().then_some(path);
+        }
+        let name = EXTENSIONS
+            .iter()
+            .find_map(|ext| icon.strip_suffix(&format!(".{}", ext)))
+            .unwrap_or(icon);
+        self.index.lookup(name, size, scale.max(1))
+    }
+
+    /// The file for `icon` at [`DEFAULT_ICON_SIZE`].
+    pub fn icon_path(&self, icon: &str) -> Option<PathBuf> {
+        self.lookup(icon, DEFAULT_ICON_SIZE, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_theme_lookup_and_freshness() {
+        let root = env::temp_dir().join(format!("jollypad-icons-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let base = root.join("icons");
+        write(
+            &base.join("Test/index.theme"),
+            "[Icon Theme]\nName=Test\nInherits=Parent\nDirectories=48x48/apps,scalable/apps\n\n\
+             [48x48/apps]\nSize=48\nType=Fixed\n\n[scalable/apps]\nSize=64\nMinSize=16\nMaxSize=128\nType=Scalable\n",
+        );
+        write(&base.join("Parent/index.theme"), "[Icon Theme]\nName=Parent\nDirectories=apps\n\n[apps]\nSize=256\n");
+        write(&base.join("hicolor/index.theme"), "[Icon Theme]\nName=Hicolor\nDirectories=256x256/apps\n\n[256x256/apps]\nSize=256\n");
+        write(&base.join("Test/48x48/apps/term.png"), "");
+        write(&base.join("Test/scalable/apps/term.svg"), "");
+        write(&base.join("Parent/apps/term.png"), "");
+        write(&base.join("hicolor/256x256/apps/steam.png"), "");
+        write(&root.join("pixmaps/old.xpm"), "");
+        write(&root.join("jollypad/steam.svg"), "");
+
+        let overrides = [root.join("jollypad")];
+        let index = IconIndex::build("Test", std::slice::from_ref(&base), &overrides, &[root.join("pixmaps")]);
+        let names: Vec<&str> = index.themes.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["Test", "Parent", "hicolor"]);
+
+        assert_eq!(index.lookup("term", 48, 1), Some(base.join("Test/48x48/apps/term.png")));
+        assert_eq!(index.lookup("term", 96, 1), Some(base.join("Test/scalable/apps/term.svg")));
+        // 256 is outside the scalable range; the child theme still wins over its parent
+        assert_eq!(index.lookup("term", 256, 1), Some(base.join("Test/scalable/apps/term.svg")));
+        assert_eq!(index.lookup("steam", 256, 1), Some(root.join("jollypad/steam.svg")));
+        assert_eq!(index.lookup("old", 64, 1), Some(root.join("pixmaps/old.xpm")));
+        assert_eq!(index.lookup("missing", 64, 1), None);
+
+        let index: IconIndex = serde_json::from_slice(&serde_json::to_vec(&index).unwrap()).unwrap();
+        assert!(index.is_fresh("Test", &overrides));
+        assert!(!index.is_fresh("Papirus", &overrides));
+        let apps = fs::File::open(base.join("Test/48x48/apps")).unwrap();
+        apps.set_modified(std::time::SystemTime::UNIX_EPOCH).unwrap();
+        assert!(!index.is_fresh("Test", &overrides));
+        let _ = fs::remove_dir_all(&root);
+    }
+}
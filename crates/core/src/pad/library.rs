@@ -0,0 +1,307 @@
+//! Per-card library data (playtime, launches, favorites, ordering), user
+//! collections and the home tab order, persisted in `~/.jolly/library.json`.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::AppItem;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LibraryEntry {
+    /// Accumulated playtime in seconds.
+    #[serde(default)]
+    pub playtime_secs: u64,
+    /// Unix timestamp of the end of the last session.
+    #[serde(default)]
+    pub last_played: Option<u64>,
+    /// Exit code of the last session; `None` if it was killed by a signal or
+    /// ended while nobody was tracking it.
+    #[serde(default)]
+    pub last_exit_code: Option<i32>,
+    #[serde(default)]
+    pub launch_count: u32,
+    /// Unix timestamp of the last launch from JollyPad.
+    #[serde(default)]
+    pub last_launched: Option<u64>,
+    #[serde(default)]
+    pub favorite: bool,
+    /// Hidden cards are left out of every grid.
+    #[serde(default)]
+    pub hidden: bool,
+    /// Position set by the user; cards without one follow alphabetically.
+    #[serde(default)]
+    pub sort_order: Option<u32>,
+}
+
+impl LibraryEntry {
+    /// Most recent of the last launch and the end of the last tracked session.
+    pub fn last_activity(&self) -> Option<u64> {
+        self.last_launched.max(self.last_played)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Library {
+    /// Entries keyed by card app_id.
+    #[serde(default)]
+    pub entries: BTreeMap<String, LibraryEntry>,
+    /// User-created collections: name -> app_ids, in the order they were added.
+    #[serde(default)]
+    pub collections: BTreeMap<String, Vec<String>>,
+    /// Home tab ids in the order the user wants them; see [`super::tabs`].
+    #[serde(default)]
+    pub tab_order: Vec<String>,
+}
+
+impl Library {
+    pub fn path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".jolly").join("library.json"))
+    }
+
+    /// Read the library file. A missing file is an empty library; one that
+    /// cannot be read or parsed is an error.
+    pub fn read() -> Result<Self> {
+        let path = Self::path().context("Could not find home directory")?;
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read library {:?}", path)),
+        };
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse library {:?}", path))
+    }
+
+    /// Load the library for display, falling back to an empty one if it is unreadable.
+    pub fn load() -> Self {
+        Self::read().unwrap_or_else(|e| {
+            eprintln!("{:#}", e);
+            Self::default()
+        })
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = Self::path().context("Could not find home directory")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).context("Failed to create library directory")?;
+        }
+        // Write to a temporary file first so a crash never leaves a truncated library
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp, &path).context("Failed to replace library file")?;
+        Ok(())
+    }
+
+    /// Load the library, apply `change` and save it, as one step. All changes to
+    /// the library file go through here.
+    ///
+    /// An exclusive `flock` on `library.json.lock` is held meanwhile, so threads
+    /// and processes (home, nav, the launch wrapper's tracker) do not overwrite
+    /// each other's changes. A library that fails to parse is left untouched
+    /// rather than replaced by an empty one.
+    pub fn update(change: impl FnOnce(&mut Self)) -> Result<()> {
+        let path = Self::path().context("Could not find home directory")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).context("Failed to create library directory")?;
+        }
+        let lock_path = path.with_extension("json.lock");
+        let lock = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .with_context(|| format!("Failed to open {:?}", lock_path))?;
+        // Released when `lock` is closed
+        lock.lock().context("Failed to lock the library")?;
+        let mut library = Self::read()?;
+        change(&mut library);
+        library.save()
+    }
+
+    pub fn entry(&self, app_id: &str) -> Option<&LibraryEntry> {
+        self.entries.get(app_id)
+    }
+
+    fn entry_mut(&mut self, app_id: &str) -> &mut LibraryEntry {
+        self.entries.entry(app_id.to_string()).or_default()
+    }
+
+    pub fn record_launch(&mut self, app_id: &str) {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let entry = self.entry_mut(app_id);
+        entry.launch_count += 1;
+        entry.last_launched = Some(now);
+    }
+
+    pub fn record_session(&mut self, app_id: &str, started: u64, ended: u64, exit_code: Option<i32>) {
+        let entry = self.entry_mut(app_id);
+        entry.playtime_secs += ended.saturating_sub(started);
+        entry.last_played = Some(ended);
+        entry.last_exit_code = exit_code;
+    }
+
+    pub fn is_favorite(&self, app_id: &str) -> bool {
+        self.entry(app_id).is_some_and(|e| e.favorite)
+    }
+
+    pub fn set_favorite(&mut self, app_id: &str, favorite: bool) {
+        self.entry_mut(app_id).favorite = favorite;
+    }
+
+    pub fn is_hidden(&self, app_id: &str) -> bool {
+        self.entry(app_id).is_some_and(|e| e.hidden)
+    }
+
+    pub fn set_hidden(&mut self, app_id: &str, hidden: bool) {
+        self.entry_mut(app_id).hidden = hidden;
+    }
+
+    /// Store a custom order: the given cards come first, in this order. Cards not
+    /// listed lose any previous position.
+    pub fn set_order(&mut self, app_ids: &[String]) {
+        for entry in self.entries.values_mut() {
+            entry.sort_order = None;
+        }
+        for (i, app_id) in app_ids.iter().enumerate() {
+            self.entry_mut(app_id).sort_order = Some(i as u32);
+        }
+    }
+
+    /// Add a card to a collection, creating the collection if needed.
+    pub fn add_to_collection(&mut self, collection: &str, app_id: &str) {
+        let members = self.collections.entry(collection.to_string()).or_default();
+        if !members.iter().any(|id| id == app_id) {
+            members.push(app_id.to_string());
+        }
+    }
+
+    /// Remove a card from a collection; a collection left empty is deleted.
+    pub fn remove_from_collection(&mut self, collection: &str, app_id: &str) {
+        if let Some(members) = self.collections.get_mut(collection) {
+            members.retain(|id| id != app_id);
+            if members.is_empty() {
+                self.collections.remove(collection);
+            }
+        }
+    }
+
+    pub fn delete_collection(&mut self, collection: &str) {
+        self.collections.remove(collection);
+    }
+
+    /// Drop everything known about a deleted card: its entry and its place in
+    /// collections. A card made later with the same id starts afresh.
+    pub fn forget(&mut self, app_id: &str) {
+        self.entries.remove(app_id);
+        let collections: Vec<String> = self.collections_of(app_id).into_iter().map(str::to_string).collect();
+        for collection in collections {
+            self.remove_from_collection(&collection, app_id);
+        }
+    }
+
+    /// Names of the collections containing `app_id`.
+    pub fn collections_of(&self, app_id: &str) -> Vec<&str> {
+        self.collections
+            .iter()
+            .filter(|(_, members)| members.iter().any(|id| id == app_id))
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Store the home tab order. Tabs not listed keep their default place after these.
+    pub fn set_tab_order(&mut self, tab_ids: &[String]) {
+        self.tab_order = tab_ids.to_vec();
+    }
+
+    /// Drop hidden cards and sort the rest: custom order first, then by name.
+    pub fn arrange(&self, items: &mut Vec<AppItem>) {
+        items.retain(|item| !self.is_hidden(&item.app_id));
+        items.sort_by_cached_key(|item| {
+            let order = self.entry(&item.app_id).and_then(|e| e.sort_order);
+            (order.is_none(), order, item.name.to_lowercase())
+        });
+    }
+
+    /// Favorite cards, in the order of `items`.
+    pub fn favorites(&self, items: &[AppItem]) -> Vec<AppItem> {
+        items.iter().filter(|item| self.is_favorite(&item.app_id)).cloned().collect()
+    }
+
+    /// Cards launched or played before, most recent first.
+    pub fn recently_played(&self, items: &[AppItem], limit: usize) -> Vec<AppItem> {
+        let mut recent: Vec<(u64, &AppItem)> = items
+            .iter()
+            .filter_map(|item| Some((self.entry(&item.app_id)?.last_activity()?, item)))
+            .collect();
+        recent.sort_by_key(|(time, _)| std::cmp::Reverse(*time));
+        recent.into_iter().take(limit).map(|(_, item)| item.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pad::{AppSource, Artwork};
+
+    fn item(name: &str) -> AppItem {
+        AppItem {
+            name: name.to_string(),
+            icon: String::new(),
+            exec: name.to_lowercase(),
+            app_id: name.to_lowercase(),
+            category: "App".to_string(),
+            source: AppSource::Manual,
+            artwork: Artwork::default(),
+        }
+    }
+
+    #[test]
+    fn test_arrange_and_rows() {
+        let mut library = Library::default();
+        library.set_hidden("browser", true);
+        library.set_order(&["music".to_string(), "files".to_string()]);
+        library.set_favorite("camera", true);
+        library.record_session("files", 100, 200, Some(0));
+        library.record_session("camera", 100, 300, Some(1));
+        assert_eq!(library.entry("camera").and_then(|e| e.last_exit_code), Some(1));
+
+        let mut items = vec![item("Files"), item("Browser"), item("Camera"), item("Music"), item("Alarm")];
+        library.arrange(&mut items);
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["Music", "Files", "Alarm", "Camera"]);
+
+        assert_eq!(library.favorites(&items)[0].name, "Camera");
+        let recent = library.recently_played(&items, 10);
+        let recent: Vec<&str> = recent.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(recent, ["Camera", "Files"]);
+    }
+
+    #[test]
+    fn test_collections() {
+        let mut library = Library::default();
+        library.add_to_collection("Couch", "kart");
+        library.add_to_collection("Couch", "party");
+        library.add_to_collection("Couch", "kart");
+        library.add_to_collection("Retro", "kart");
+        assert_eq!(library.collections["Couch"], ["kart", "party"]);
+        assert_eq!(library.collections_of("kart"), ["Couch", "Retro"]);
+
+        library.remove_from_collection("Retro", "kart");
+        assert!(!library.collections.contains_key("Retro"));
+
+        library.set_favorite("kart", true);
+        library.forget("kart");
+        assert!(library.entry("kart").is_none());
+        assert_eq!(library.collections.keys().collect::<Vec<_>>(), ["Couch"]);
+        assert_eq!(library.collections["Couch"], ["party"]);
+        library.forget("party");
+        assert!(library.collections.is_empty());
+
+        // Older library files have neither field
+        let old: Library = serde_json::from_str(r#"{"entries":{}}"#).unwrap();
+        assert!(old.collections.is_empty() && old.tab_order.is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+//! Lutris library import.
+//!
+//! Installed games come from Lutris' `pga.db`. When the database cannot be read,
+//! the per-game YAML configs are used instead, taking the `name` and `game_slug`
+//! they carry or, failing that, naming games after the slug in the file name.
+//!
+//! Games are launched through `lutris:rungame/`, so Lutris starts them outside
+//! the launch wrapper: there is no session, playtime, hooks or launch log (see
+//! [`crate::session`]), only a launch count.
+
+use rusqlite::{Connection, OpenFlags};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{AppItem, AppSource, Artwork};
+
+#[derive(Debug, Clone)]
+struct LutrisData {
+    /// Directory holding `pga.db`, `coverart/` and `banners/`.
+    data: PathBuf,
+    /// Directory holding the per-game `games/*.yml`.
+    config: PathBuf,
+    flatpak: bool,
+}
+
+fn lutris_dirs() -> Vec<LutrisData> {
+    let Some(home) = dirs::home_dir() else { return Vec::new() };
+    let data = dirs::data_dir().unwrap_or_else(|| home.join(".local").join("share"));
+    let config = dirs::config_dir().unwrap_or_else(|| home.join(".config"));
+    let flatpak = home.join(".var/app/net.lutris.Lutris");
+    [
+        LutrisData { data: data.join("lutris"), config: config.join("lutris"), flatpak: false },
+        LutrisData {
+            data: flatpak.join("data/lutris"),
+            config: flatpak.join("config/lutris"),
+            flatpak: true,
+        },
+    ]
+    .into_iter()
+    .filter(|install| install.data.is_dir() || install.config.is_dir())
+    .collect()
+}
+
+#[derive(Debug, PartialEq)]
+pub struct LutrisGame {
+    pub slug: String,
+    pub name: String,
+}
+
+/// Installed games from `pga.db`, opened read-only so a running Lutris is not disturbed.
+pub fn read_pga_db(path: &Path) -> rusqlite::Result<Vec<LutrisGame>> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut stmt = conn.prepare(
+        "SELECT slug, name FROM games WHERE installed = 1 AND slug IS NOT NULL AND name IS NOT NULL ORDER BY name",
+    )?;
+    let games = stmt
+        .query_map([], |row| Ok(LutrisGame { slug: row.get(0)?, name: row.get(1)? }))?
+        .collect();
+    games
+}
+
+/// Games from the YAML configs, which are named `<slug>-<timestamp>.yml`.
+fn read_game_configs(dir: &Path) -> Vec<LutrisGame> {
+    let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+    let mut games: Vec<LutrisGame> = entries
+        .flatten()
+        .filter_map(|e| {
+            let path = e.path();
+            if path.extension().is_none_or(|x| x != "yml") {
+                return None;
+            }
+            let stem = path.file_stem()?.to_str()?;
+            let content = fs::read_to_string(&path).unwrap_or_default();
+            Some(game_from_config(stem, &content))
+        })
+        .collect();
+    games.sort_by(|a, b| a.slug.cmp(&b.slug));
+    games.dedup_by(|a, b| a.slug == b.slug);
+    games
+}
+
+/// The game of a config file: its top-level `name` and `game_slug`, with the
+/// file name standing in for what is missing.
+fn game_from_config(stem: &str, content: &str) -> LutrisGame {
+    let slug = top_level_value(content, "game_slug").unwrap_or_else(|| slug_from_config_name(stem).to_string());
+    let name = top_level_value(content, "name").unwrap_or_else(|| name_from_slug(&slug));
+    LutrisGame { slug, name }
+}
+
+/// A plain `key: value` of the YAML root; nested keys are indented and skipped.
+fn top_level_value(content: &str, key: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let value = line.strip_prefix(key)?.strip_prefix(':')?.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+            .unwrap_or(value);
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+fn slug_from_config_name(stem: &str) -> &str {
+    match stem.rsplit_once('-') {
+        Some((slug, stamp)) if !slug.is_empty() && stamp.chars().all(|c| c.is_ascii_digit()) => slug,
+        _ => stem,
+    }
+}
+
+fn name_from_slug(slug: &str) -> String {
+    slug.split('-')
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn find_icon(install: &LutrisData, slug: &str) -> Option<PathBuf> {
+    let mut candidates = vec![
+        install.data.join("coverart").join(format!("{}.jpg", slug)),
+        install.data.join("banners").join(format!("{}.jpg", slug)),
+    ];
+    // Lutris puts icons in the share directory it runs with, which is the sandbox's
+    // own for the Flatpak
+    if let Some(share) = install.data.parent() {
+        candidates.push(share.join("icons/hicolor/128x128/apps").join(format!("lutris_{}.png", slug)));
+    }
+    candidates.into_iter().find(|p| p.is_file())
+}
+
+/// Cards for every installed Lutris game.
+pub fn import_lutris_games() -> Vec<AppItem> {
+    let mut apps: Vec<AppItem> = Vec::new();
+    for install in lutris_dirs() {
+        let db = install.data.join("pga.db");
+        let games = match read_pga_db(&db) {
+            Ok(games) => games,
+            Err(e) => {
+                if db.exists() {
+                    eprintln!("Failed to read Lutris database {:?}: {}", db, e);
+                }
+                read_game_configs(&install.config.join("games"))
+            }
+        };
+
+        let lutris = if install.flatpak { "flatpak run net.lutris.Lutris" } else { "lutris" };
+        for game in games {
+            let app_id = format!("lutris_{}", game.slug);
+            if apps.iter().any(|a| a.app_id == app_id) {
+                continue;
+            }
+            let cover = install.data.join("coverart").join(format!("{}.jpg", game.slug));
+            let cover = if cover.is_file() { cover.to_string_lossy().into_owned() } else { String::new() };
+            let icon = find_icon(&install, &game.slug)
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "lutris".to_string());
+            apps.push(AppItem {
+                name: game.name,
+                icon,
+                exec: format!("{} lutris:rungame/{}", lutris, game.slug),
+                app_id,
+                category: "Game".to_string(),
+                source: AppSource::Lutris,
+                artwork: Artwork { cover, ..Default::default() },
+            });
+        }
+    }
+    apps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_pga_db() {
+        let path = std::env::temp_dir().join(format!("jollypad-pga-{}.db", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE games (id INTEGER PRIMARY KEY, name TEXT, slug TEXT, installed INTEGER);
+             INSERT INTO games (name, slug, installed) VALUES ('World of Goo', 'world-of-goo', 1);
+             INSERT INTO games (name, slug, installed) VALUES ('Not Installed', 'not-installed', 0);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let games = read_pga_db(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(games, [LutrisGame { slug: "world-of-goo".into(), name: "World of Goo".into() }]);
+    }
+
+    #[test]
+    fn test_slug_from_config_name() {
+        assert_eq!(slug_from_config_name("world-of-goo-1688301234"), "world-of-goo");
+        assert_eq!(slug_from_config_name("quake"), "quake");
+        assert_eq!(name_from_slug("world-of-goo"), "World Of Goo");
+    }
+
+    #[test]
+    fn test_game_from_config() {
+        let config = "game:\n  name: nested\n  exe: /games/goo/WorldOfGoo\nname: 'World of Goo'\ngame_slug: world-of-goo\n";
+        let game = game_from_config("world-of-goo-1688301234", config);
+        assert_eq!(game, LutrisGame { slug: "world-of-goo".into(), name: "World of Goo".into() });
+        let game = game_from_config("quake-1688301234", "game:\n  exe: quake\n");
+        assert_eq!(game, LutrisGame { slug: "quake".into(), name: "Quake".into() });
+    }
+}
@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use dirs;
+
+use card_format::{Card, ParseError};
+
+pub mod card_format;
+pub mod cards;
+pub mod desktop;
+pub mod heroic;
+pub mod icons;
+pub mod library;
+pub mod search;
+pub mod lutris;
+pub mod steam;
+pub mod tabs;
+
+pub use icons::IconLoader;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppItem {
+    pub name: String,
+    pub icon: String, // icon name or absolute path
+    pub exec: String,
+    pub app_id: String,
+    pub category: String,
+    #[serde(default)]
+    pub source: AppSource,
+    #[serde(default)]
+    pub artwork: Artwork,
+}
+
+impl AppItem {
+    /// The card's categories; `Category=` may list several, separated by commas.
+    pub fn categories(&self) -> impl Iterator<Item = &str> {
+        self.category.split(',').map(str::trim).filter(|c| !c.is_empty())
+    }
+
+    /// Imported Steam, Heroic and Lutris games are all in the Game category.
+    pub fn is_game(&self) -> bool {
+        self.categories().any(|c| c.eq_ignore_ascii_case("Game"))
+    }
+}
+
+/// Game artwork, each an image path; empty when the card has none.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Artwork {
+    /// Portrait box art, shown on the card instead of the icon.
+    pub cover: String,
+    /// Wide banner shown behind the selected card.
+    pub hero: String,
+    /// The game's title logo.
+    pub logo: String,
+    /// Full screen picture, preferred over the hero behind the selected card.
+    pub background: String,
+}
+
+/// Where a card comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AppSource {
+    /// An `.ini` card under `~/.jolly/app`.
+    #[default]
+    Manual,
+    /// An installed game imported from the Steam library.
+    Steam,
+    /// An installed Epic or GOG game imported from Heroic.
+    Heroic,
+    /// An installed game imported from Lutris.
+    Lutris,
+    /// A freedesktop `.desktop` application entry.
+    Desktop,
+}
+
+/// Turn a manual card into a pad item. Games start through game-launcher,
+/// which reads the card again for Proton, hooks and environment.
+fn parse_ini_app(content: &str, app_id: &str) -> Result<AppItem, ParseError> {
+    let card = Card::parse(content)?;
+    for skipped in &card.skipped {
+        eprintln!("Card {}: skipping {}", app_id, skipped);
+    }
+    let (exec, default_category) = if card.is_game {
+        (format!("game-launcher {}", app_id), "Game")
+    } else {
+        (card.exec, "App")
+    };
+    let category = if card.category.trim().is_empty() {
+        default_category.to_string()
+    } else if card.is_game && !card.category.split(',').any(|c| c.trim().eq_ignore_ascii_case("Game")) {
+        // Games stay in the Game tab whatever else they are filed under
+        format!("Game,{}", card.category)
+    } else {
+        card.category
+    };
+    Ok(AppItem {
+        name: card.name,
+        icon: card.icon,
+        exec,
+        app_id: app_id.to_string(),
+        category,
+        source: AppSource::Manual,
+        artwork: card.artwork,
+    })
+}
+
+pub fn get_default_items(_icon_loader: &IconLoader) -> Vec<AppItem> {
+    let mut apps = Vec::new();
+    if let Some(home) = dirs::home_dir() {
+        let app_dir = home.join(".jolly").join("app");
+        if let Ok(entries) = fs::read_dir(app_dir) {
+            for e in entries.flatten() {
+                let path = e.path();
+                if path.extension().map_or(false, |x| x == "ini") {
+                    let default_app_id = path.file_stem()
+                        .and_then(|s| s.to_str())
+                        .unwrap_or("")
+                        .to_string();
+                    if let Ok(content) = fs::read_to_string(&path) {
+                        match parse_ini_app(&content, &default_app_id) {
+                            Ok(app) => apps.push(app),
+                            Err(e) => eprintln!("Skipping card {:?}: {}", path, e),
+                        }
+                    }
+                }
+            }
+        }
+    }
+    // Imported on every call so installs and uninstalls show up on the next start.
+    // Manual cards win over imported ones for the same game.
+    let imported = steam::import_steam_games()
+        .into_iter()
+        .chain(heroic::import_heroic_games())
+        .chain(lutris::import_lutris_games());
+    for game in imported {
+        // Imported execs end with the launcher URL, e.g. steam://rungameid/620
+        let url = game.exec.rsplit_once(' ').map(|(_, url)| url).unwrap_or_default();
+        if !apps.iter().any(|a| a.app_id == game.app_id || a.exec.split_whitespace().any(|t| t == url)) {
+            apps.push(game);
+        }
+    }
+    // Launchers also write .desktop shortcuts for their games, which then have the same Exec
+    for app in desktop::scan_desktop_entries() {
+        let argv = shell_words::split(&app.exec).ok();
+        if !apps.iter().any(|a| a.app_id == app.app_id || shell_words::split(&a.exec).ok() == argv) {
+            apps.push(app);
+        }
+    }
+    library::Library::load().arrange(&mut apps);
+    apps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_loose_ini() {
+        let content = r#"
+        [App]
+        Name=Test App
+        Icon=test.png
+        Exec=test_exec
+        "#;
+        let app = parse_ini_app(content, "test.app").unwrap();
+        assert_eq!(app.name, "Test App");
+        assert_eq!(app.icon, "test.png");
+        assert_eq!(app.exec, "test_exec");
+    }
+
+    #[test]
+    fn test_parse_stray_lines() {
+        // Cards that loaded before the stricter parser must keep loading
+        let content = "Version=1\n[App]\nName=Stray\nsome note\nExec=stray\n";
+        let app = parse_ini_app(content, "stray").unwrap();
+        assert_eq!(app.name, "Stray");
+        assert_eq!(app.exec, "stray");
+    }
+
+    #[test]
+    fn test_bom_header() {
+        // Simulate BOM at start of [Game]
+        let content = "\u{FEFF}[Game]\nName=Test Game\nType=Game\nIcon=test.png\nExec=test";
+        let app = parse_ini_app(content, "com.test").unwrap();
+        assert_eq!(app.name, "Test Game");
+    }
+
+    #[test]
+    fn test_is_game() {
+        let mut app = parse_ini_app("[App]\nName=Kart\nExec=kart\nCategory=Racing, game\n", "kart").unwrap();
+        assert!(app.is_game());
+        app.category = "Utility".to_string();
+        assert!(!app.is_game());
+    }
+}
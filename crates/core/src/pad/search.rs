@@ -0,0 +1,126 @@
+//! Incremental search over the library, as typed on the home search bar.
+//!
+//! Every word of the query has to match one of a card's name, app_id or
+//! categories as a fuzzy subsequence ("hknt" finds "Hollow Knight"). Matches at
+//! the start of a word and runs of consecutive letters score higher, and the
+//! name counts more than the other fields.
+
+use super::library::Library;
+use super::{AppItem, AppSource};
+
+/// Filters applied before matching, from the chips under the search bar.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchFilter {
+    /// Only cards from this source.
+    pub source: Option<AppSource>,
+    pub favorites: bool,
+    /// Only cards launched or played before.
+    pub recent: bool,
+}
+
+impl SearchFilter {
+    pub fn matches(&self, item: &AppItem, library: &Library) -> bool {
+        self.source.is_none_or(|source| item.source == source)
+            && (!self.favorites || library.is_favorite(&item.app_id))
+            && (!self.recent || library.entry(&item.app_id).is_some_and(|e| e.last_activity().is_some()))
+    }
+}
+
+/// Score of `query` as a case-insensitive subsequence of `text`, or `None` if
+/// it is not one. An empty query matches everything with score 0.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut previous: Option<usize> = None;
+    for q in query.chars().flat_map(char::to_lowercase) {
+        let found = pos + text[pos..].iter().position(|&c| c == q)?;
+        score += 1;
+        if previous == Some(found.wrapping_sub(1)) {
+            score += 4;
+        }
+        if found == 0 {
+            score += 6;
+        } else if !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        pos = found + 1;
+    }
+    Some(score)
+}
+
+fn item_score(word: &str, item: &AppItem) -> Option<u32> {
+    let name = fuzzy_score(word, &item.name).map(|s| s * 2);
+    let app_id = fuzzy_score(word, &item.app_id);
+    let category = item.categories().filter_map(|c| fuzzy_score(word, c)).max();
+    name.max(app_id).max(category)
+}
+
+/// Cards passing `filter` that match `query`, best match first. Cards that
+/// score the same keep their order in `items`.
+pub fn search(items: &[AppItem], library: &Library, query: &str, filter: &SearchFilter) -> Vec<AppItem> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    let mut results: Vec<(u32, &AppItem)> = items
+        .iter()
+        .filter(|item| filter.matches(item, library))
+        .filter_map(|item| {
+            let score = words.iter().map(|word| item_score(word, item)).sum::<Option<u32>>()?;
+            Some((score, item))
+        })
+        .collect();
+    results.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    results.into_iter().map(|(_, item)| item.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pad::Artwork;
+
+    fn item(name: &str, app_id: &str, category: &str, source: AppSource) -> AppItem {
+        AppItem {
+            name: name.to_string(),
+            icon: String::new(),
+            exec: app_id.to_string(),
+            app_id: app_id.to_string(),
+            category: category.to_string(),
+            source,
+            artwork: Artwork::default(),
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert_eq!(fuzzy_score("xyz", "Hollow Knight"), None);
+        assert!(fuzzy_score("hk", "Hollow Knight").is_some());
+        // Prefixes and word starts beat letters from the middle of a word
+        assert!(fuzzy_score("kni", "Hollow Knight") > fuzzy_score("low", "Hollow Knight"));
+        assert!(fuzzy_score("hol", "Hollow Knight") > fuzzy_score("kni", "Hollow Knight"));
+    }
+
+    #[test]
+    fn test_search() {
+        let items = vec![
+            item("Celeste", "celeste", "Game", AppSource::Steam),
+            item("Files", "org.gnome.Nautilus", "App", AppSource::Desktop),
+            item("Hollow Knight", "hollow-knight", "Game,Metroidvania", AppSource::Manual),
+        ];
+        let mut library = Library::default();
+        library.set_favorite("celeste", true);
+        let names = |results: Vec<AppItem>| results.into_iter().map(|i| i.name).collect::<Vec<_>>();
+
+        assert_eq!(names(search(&items, &library, "hol kni", &SearchFilter::default())), ["Hollow Knight"]);
+        assert_eq!(names(search(&items, &library, "nautilus", &SearchFilter::default())), ["Files"]);
+        assert_eq!(names(search(&items, &library, "metroid", &SearchFilter::default())), ["Hollow Knight"]);
+        assert_eq!(names(search(&items, &library, "game", &SearchFilter::default())), ["Celeste", "Hollow Knight"]);
+
+        let steam = SearchFilter { source: Some(AppSource::Steam), ..Default::default() };
+        assert_eq!(names(search(&items, &library, "", &steam)), ["Celeste"]);
+        let favorites = SearchFilter { favorites: true, ..Default::default() };
+        assert_eq!(names(search(&items, &library, "", &favorites)), ["Celeste"]);
+        let recent = SearchFilter { recent: true, ..Default::default() };
+        assert!(search(&items, &library, "", &recent).is_empty());
+    }
+}
@@ -0,0 +1,350 @@
+//! Steam library import.
+//!
+//! Reads `libraryfolders.vdf` and `appmanifest_*.acf` from every Steam install
+//! found in the home directory (native and Flatpak) and turns installed games
+//! into cards launched through `steam://rungameid/`.
+//!
+//! The running Steam client starts the game, so it does not run in the launch
+//! wrapper: Steam cards get no session, playtime, hooks or launch log (see
+//! [`crate::session`]), only a launch count. A running one is recognised by its
+//! `steam_app_<id>` window.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{AppItem, AppSource, Artwork};
+
+/// Steam's "fully installed" bit in `StateFlags`.
+const STATE_FULLY_INSTALLED: u32 = 4;
+
+/// Redistributables, runtimes and compatibility tools that show up as apps.
+const TOOL_APP_IDS: &[&str] = &["228980", "1070560", "1391110", "1628350", "1493710", "250820"];
+const TOOL_NAME_PREFIXES: &[&str] = &["Proton", "Steam Linux Runtime", "Steamworks Common"];
+
+/// A parsed KeyValues (VDF/ACF) node.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Vdf {
+    Value(String),
+    Map(Vec<(String, Vdf)>),
+}
+
+impl Vdf {
+    /// Child by key, case-insensitive like Steam itself.
+    pub fn get(&self, key: &str) -> Option<&Vdf> {
+        match self {
+            Vdf::Map(entries) => entries.iter().find(|(k, _)| k.eq_ignore_ascii_case(key)).map(|(_, v)| v),
+            Vdf::Value(_) => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Vdf::Value(v) => Some(v),
+            Vdf::Map(_) => None,
+        }
+    }
+
+    pub fn entries(&self) -> &[(String, Vdf)] {
+        match self {
+            Vdf::Map(entries) => entries,
+            Vdf::Value(_) => &[],
+        }
+    }
+}
+
+/// Parse KeyValues text into its root map.
+pub fn parse_vdf(text: &str) -> Vdf {
+    let tokens = tokenize(text);
+    let mut pos = 0;
+    Vdf::Map(parse_map(&tokens, &mut pos))
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Str(String),
+    Open,
+    Close,
+}
+
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => tokens.push(Token::Open),
+            '}' => tokens.push(Token::Close),
+            '"' => {
+                let mut s = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => s.push('\n'),
+                            Some('t') => s.push('\t'),
+                            Some(other) => s.push(other),
+                            None => break,
+                        },
+                        _ => s.push(c),
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' { break; }
+                }
+            }
+            // Platform conditionals like [$WIN32] are not needed for manifests
+            '[' => {
+                for c in chars.by_ref() {
+                    if c == ']' { break; }
+                }
+            }
+            c if c.is_whitespace() => {}
+            _ => {
+                let mut s = String::from(c);
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '{' || c == '}' || c == '"' { break; }
+                    s.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Str(s));
+            }
+        }
+    }
+    tokens
+}
+
+fn parse_map(tokens: &[Token], pos: &mut usize) -> Vec<(String, Vdf)> {
+    let mut entries = Vec::new();
+    while *pos < tokens.len() {
+        let key = match &tokens[*pos] {
+            Token::Str(key) => key.clone(),
+            Token::Close => {
+                *pos += 1;
+                break;
+            }
+            Token::Open => {
+                // Stray brace, skip it
+                *pos += 1;
+                continue;
+            }
+        };
+        *pos += 1;
+        match tokens.get(*pos) {
+            Some(Token::Str(value)) => {
+                entries.push((key, Vdf::Value(value.clone())));
+                *pos += 1;
+            }
+            Some(Token::Open) => {
+                *pos += 1;
+                entries.push((key, Vdf::Map(parse_map(tokens, pos))));
+            }
+            Some(Token::Close) | None => {}
+        }
+    }
+    entries
+}
+
+/// A Steam install root and how to launch its client.
+#[derive(Debug, Clone)]
+struct SteamRoot {
+    path: PathBuf,
+    flatpak: bool,
+}
+
+fn steam_roots() -> Vec<SteamRoot> {
+    let Some(home) = dirs::home_dir() else { return Vec::new() };
+    let candidates = [
+        (home.join(".steam").join("steam"), false),
+        (home.join(".local").join("share").join("Steam"), false),
+        (home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"), true),
+    ];
+    let mut seen = HashSet::new();
+    let mut roots = Vec::new();
+    for (path, flatpak) in candidates {
+        // ~/.steam/steam is usually a symlink to ~/.local/share/Steam
+        let Ok(canonical) = fs::canonicalize(&path) else { continue };
+        if canonical.join("steamapps").is_dir() && seen.insert(canonical.clone()) {
+            roots.push(SteamRoot { path: canonical, flatpak });
+        }
+    }
+    roots
+}
+
+/// Library folders listed in `libraryfolders.vdf`, including the root itself.
+fn library_folders(root: &Path) -> Vec<PathBuf> {
+    let mut folders = vec![root.to_path_buf()];
+    let vdf_path = root.join("steamapps").join("libraryfolders.vdf");
+    if let Ok(content) = fs::read_to_string(vdf_path) {
+        let vdf = parse_vdf(&content);
+        if let Some(libraries) = vdf.get("libraryfolders") {
+            for (_, library) in libraries.entries() {
+                // Old format: "1" "/path"; new format: "1" { "path" "/path" ... }
+                let path = library.as_str().or_else(|| library.get("path").and_then(Vdf::as_str));
+                if let Some(path) = path {
+                    let path = fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+                    if !folders.contains(&path) {
+                        folders.push(path);
+                    }
+                }
+            }
+        }
+    }
+    folders
+}
+
+/// Installed game from an `appmanifest_*.acf`.
+#[derive(Debug, PartialEq)]
+pub struct SteamApp {
+    pub app_id: String,
+    pub name: String,
+}
+
+pub fn parse_app_manifest(content: &str) -> Option<SteamApp> {
+    let vdf = parse_vdf(content);
+    let state = vdf.get("AppState")?;
+    let app_id = state.get("appid")?.as_str()?.to_string();
+    let name = state.get("name")?.as_str()?.trim().to_string();
+    let flags: u32 = state.get("StateFlags").and_then(Vdf::as_str).and_then(|f| f.parse().ok()).unwrap_or(0);
+    if name.is_empty() || flags & STATE_FULLY_INSTALLED == 0 {
+        return None;
+    }
+    if TOOL_APP_IDS.contains(&app_id.as_str()) || TOOL_NAME_PREFIXES.iter().any(|p| name.starts_with(p)) {
+        return None;
+    }
+    Some(SteamApp { app_id, name })
+}
+
+/// Local artwork for a game from `appcache/librarycache`, in order of preference.
+fn find_artwork(root: &Path, app_id: &str) -> Option<PathBuf> {
+    let cache = root.join("appcache").join("librarycache");
+    // Legacy flat layout: <appid>_icon.jpg, <appid>_library_600x900.jpg, ...
+    for suffix in ["icon.jpg", "library_600x900.jpg", "header.jpg"] {
+        let p = cache.join(format!("{}_{}", app_id, suffix));
+        if p.is_file() {
+            return Some(p);
+        }
+    }
+    // Current layout: <appid>/ with named files plus the icon stored under its hash
+    let dir = cache.join(app_id);
+    if let Ok(entries) = fs::read_dir(&dir) {
+        for entry in entries.flatten() {
+            let p = entry.path();
+            let Some(stem) = p.file_stem().and_then(|s| s.to_str()) else { continue };
+            if p.extension().is_some_and(|x| x == "jpg") && stem.len() == 40 && stem.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Some(p);
+            }
+        }
+    }
+    ["library_600x900.jpg", "header.jpg"].iter().map(|f| dir.join(f)).find(|p| p.is_file())
+}
+
+/// Box art, hero banner and logo Steam downloaded for its library view.
+fn find_library_art(root: &Path, app_id: &str) -> Artwork {
+    let cache = root.join("appcache").join("librarycache");
+    let find = |file: &str| {
+        [cache.join(format!("{}_{}", app_id, file)), cache.join(app_id).join(file)]
+            .into_iter()
+            .find(|p| p.is_file())
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+    Artwork {
+        cover: find("library_600x900.jpg"),
+        hero: find("library_hero.jpg"),
+        logo: find("logo.png"),
+        background: String::new(),
+    }
+}
+
+/// Cards for every installed Steam game.
+pub fn import_steam_games() -> Vec<AppItem> {
+    let mut apps = Vec::new();
+    let mut seen = HashSet::new();
+    for root in steam_roots() {
+        for folder in library_folders(&root.path) {
+            let Ok(entries) = fs::read_dir(folder.join("steamapps")) else { continue };
+            for entry in entries.flatten() {
+                let fname = entry.file_name();
+                let fname = fname.to_string_lossy();
+                if !(fname.starts_with("appmanifest_") && fname.ends_with(".acf")) {
+                    continue;
+                }
+                let Ok(content) = fs::read_to_string(entry.path()) else { continue };
+                let Some(game) = parse_app_manifest(&content) else { continue };
+                if !seen.insert(game.app_id.clone()) {
+                    continue;
+                }
+                let steam = if root.flatpak { "flatpak run com.valvesoftware.Steam" } else { "steam" };
+                let icon = find_artwork(&root.path, &game.app_id)
+                    .map(|p| p.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "steam".to_string());
+                apps.push(AppItem {
+                    name: game.name,
+                    icon,
+                    exec: format!("{} steam://rungameid/{}", steam, game.app_id),
+                    // Steam games run with this window app_id under Wayland/XWayland
+                    app_id: format!("steam_app_{}", game.app_id),
+                    category: "Game".to_string(),
+                    source: AppSource::Steam,
+                    artwork: find_library_art(&root.path, &game.app_id),
+                });
+            }
+        }
+    }
+    apps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_library_folders() {
+        let content = r#"
+"libraryfolders"
+{
+	"0"
+	{
+		"path"		"/home/user/.local/share/Steam"
+		"apps"
+		{
+			"620"		"12345"
+		}
+	}
+	"1"
+	{
+		"path"		"/mnt/games/Steam\\Library"
+	}
+}"#;
+        let vdf = parse_vdf(content);
+        let libraries = vdf.get("LibraryFolders").unwrap();
+        let paths: Vec<&str> = libraries.entries().iter()
+            .filter_map(|(_, l)| l.get("path").and_then(Vdf::as_str))
+            .collect();
+        assert_eq!(paths, ["/home/user/.local/share/Steam", "/mnt/games/Steam\\Library"]);
+        assert_eq!(libraries.get("0").unwrap().get("apps").unwrap().get("620"), Some(&Vdf::Value("12345".into())));
+    }
+
+    #[test]
+    fn test_parse_app_manifest() {
+        let manifest = r#"
+"AppState"
+{
+	"appid"		"620"
+	"Universe"		"1"
+	"name"		"Portal 2"
+	"StateFlags"		"4"
+	"installdir"		"Portal 2" // trailing comment
+}"#;
+        assert_eq!(parse_app_manifest(manifest), Some(SteamApp { app_id: "620".into(), name: "Portal 2".into() }));
+
+        let downloading = manifest.replace("\"StateFlags\"\t\t\"4\"", "\"StateFlags\"\t\t\"1026\"");
+        assert_eq!(parse_app_manifest(&downloading), None);
+
+        let runtime = manifest.replace("Portal 2", "Steam Linux Runtime 3.0 (sniper)");
+        assert_eq!(parse_app_manifest(&runtime), None);
+    }
+}
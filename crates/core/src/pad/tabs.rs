@@ -0,0 +1,155 @@
+//! The tab bar of home, built from card categories and user collections.
+//!
+//! Every tab has a stable id, which is what [`Library::tab_order`] stores:
+//!
+//! - `all`, `favorites` and `recent`
+//! - `category:<name>` for each `Category=` value found on a card, lowercased
+//! - `collection:<name>` for each non-empty collection in the library
+//!
+//! By default the tabs come as All, the categories alphabetically, Favorites,
+//! Recent and the collections. Ids listed in `tab_order` move to the front in
+//! that order; listed ids that no longer exist are skipped. `App`, the category
+//! of cards that have none, gets no tab since it would repeat All.
+
+use std::collections::BTreeMap;
+
+use super::library::Library;
+use super::AppItem;
+
+/// Category given to cards without a `Category=`.
+const FALLBACK_CATEGORY: &str = "App";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TabKind {
+    All,
+    Favorites,
+    Recent,
+    /// A card category, as first spelled on a card.
+    Category(String),
+    /// A user collection from the library.
+    Collection(String),
+}
+
+impl TabKind {
+    pub fn id(&self) -> String {
+        match self {
+            TabKind::All => "all".to_string(),
+            TabKind::Favorites => "favorites".to_string(),
+            TabKind::Recent => "recent".to_string(),
+            TabKind::Category(name) => format!("category:{}", name.to_lowercase()),
+            TabKind::Collection(name) => format!("collection:{}", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Tab {
+    pub kind: TabKind,
+    pub items: Vec<AppItem>,
+}
+
+impl Tab {
+    pub fn id(&self) -> String {
+        self.kind.id()
+    }
+}
+
+/// The home tabs for `items`, which should already be arranged by
+/// [`Library::arrange`]. `recent_limit` caps the Recent tab.
+pub fn build_tabs(items: &[AppItem], library: &Library, recent_limit: usize) -> Vec<Tab> {
+    // Categories are matched case-insensitively and keep their first spelling
+    let mut categories: BTreeMap<String, (String, Vec<AppItem>)> = BTreeMap::new();
+    for item in items {
+        let mut seen = Vec::new();
+        for category in item.categories() {
+            let key = category.to_lowercase();
+            if category.eq_ignore_ascii_case(FALLBACK_CATEGORY) || seen.contains(&key) {
+                continue;
+            }
+            seen.push(key.clone());
+            categories
+                .entry(key)
+                .or_insert_with(|| (category.to_string(), Vec::new()))
+                .1
+                .push(item.clone());
+        }
+    }
+
+    let mut tabs = vec![Tab { kind: TabKind::All, items: items.to_vec() }];
+    tabs.extend(
+        categories
+            .into_values()
+            .map(|(name, items)| Tab { kind: TabKind::Category(name), items }),
+    );
+    tabs.push(Tab { kind: TabKind::Favorites, items: library.favorites(items) });
+    tabs.push(Tab { kind: TabKind::Recent, items: library.recently_played(items, recent_limit) });
+    for (name, members) in &library.collections {
+        // Members keep the order they were added in; removed or hidden cards drop out
+        let members: Vec<AppItem> = members
+            .iter()
+            .filter_map(|id| items.iter().find(|item| &item.app_id == id).cloned())
+            .collect();
+        if !members.is_empty() {
+            tabs.push(Tab { kind: TabKind::Collection(name.clone()), items: members });
+        }
+    }
+
+    let position = |tab: &Tab| {
+        let id = tab.id();
+        library.tab_order.iter().position(|ordered| ordered.eq_ignore_ascii_case(&id))
+    };
+    // Stable, so unlisted tabs keep their default order
+    tabs.sort_by_key(|tab| position(tab).unwrap_or(usize::MAX));
+    tabs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pad::{AppSource, Artwork};
+
+    fn item(app_id: &str, category: &str) -> AppItem {
+        AppItem {
+            name: app_id.to_string(),
+            icon: String::new(),
+            exec: app_id.to_string(),
+            app_id: app_id.to_string(),
+            category: category.to_string(),
+            source: AppSource::Manual,
+            artwork: Artwork::default(),
+        }
+    }
+
+    #[test]
+    fn test_build_tabs() {
+        let items = vec![
+            item("kart", "Game, Couch"),
+            item("mpv", "Media"),
+            item("foot", "App"),
+            item("chess", "game,game"),
+        ];
+        let mut library = Library::default();
+        library.add_to_collection("Party", "chess");
+        library.add_to_collection("Party", "kart");
+        library.add_to_collection("Gone", "uninstalled");
+
+        let tabs = build_tabs(&items, &library, 10);
+        let ids: Vec<String> = tabs.iter().map(Tab::id).collect();
+        assert_eq!(
+            ids,
+            ["all", "category:couch", "category:game", "category:media", "favorites", "recent", "collection:Party"]
+        );
+        assert_eq!(tabs[2].kind, TabKind::Category("Game".to_string()));
+        let games: Vec<&str> = tabs[2].items.iter().map(|i| i.app_id.as_str()).collect();
+        assert_eq!(games, ["kart", "chess"]);
+        let party: Vec<&str> = tabs[6].items.iter().map(|i| i.app_id.as_str()).collect();
+        assert_eq!(party, ["chess", "kart"]);
+
+        library.set_tab_order(&["collection:Party".to_string(), "Category:Game".to_string(), "missing".to_string()]);
+        let ids: Vec<String> = build_tabs(&items, &library, 10).iter().map(Tab::id).collect();
+        assert_eq!(
+            ids,
+            ["collection:Party", "category:game", "all", "category:couch", "category:media", "favorites", "recent"]
+        );
+    }
+}
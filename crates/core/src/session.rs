@@ -0,0 +1,351 @@
+//! Game session tracking.
+//!
+//! The launch wrapper built by `game_launcher` writes a session file per card
+//! into `$XDG_RUNTIME_DIR/jollypad/sessions`:
+//!
+//! ```text
+//! pid=<wrapper pid>
+//! started=<unix time>
+//! exit=<exit code>     # appended when the main process returns
+//! ended=<unix time>
+//! ```
+//!
+//! The tracker follows the process tree below the wrapper through `/proc`, so
+//! launchers that fork and exit (or games that never map a window) are still
+//! seen as running. Once no process of the tree is left, the session is
+//! finished and its playtime is added to the [`Library`].
+//!
+//! Only `game-launcher` cards run in the wrapper, so only they get sessions,
+//! playtime, hooks and launch logs. Imported Steam, Heroic and Lutris games are
+//! started by their launcher's running client, outside any process tree we
+//! could follow; they count launches, and running ones are found by window.
+
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::pad::library::Library;
+
+/// A process identified by PID and start time, so recycled PIDs are not mistaken
+/// for the original process.
+type ProcKey = (u32, u64);
+
+#[derive(Debug)]
+struct TrackedSession {
+    started: u64,
+    root: u32,
+    procs: HashSet<ProcKey>,
+    last_seen: u64,
+    exit_code: Option<i32>,
+    ended: Option<u64>,
+}
+
+/// A session whose process tree has fully exited.
+#[derive(Debug, Clone)]
+pub struct FinishedSession {
+    pub card_id: String,
+    pub started: u64,
+    pub ended: u64,
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Debug, Default)]
+pub struct SessionTracker {
+    sessions: HashMap<String, TrackedSession>,
+}
+
+impl SessionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rescan session files and `/proc`, finishing sessions whose processes are gone.
+    pub fn refresh(&mut self) -> Vec<FinishedSession> {
+        let now = unix_now();
+        let dir = sessions_dir();
+        let mut seen_cards = HashSet::new();
+
+        if let Ok(entries) = fs::read_dir(&dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().is_none_or(|x| x != "session") {
+                    continue;
+                }
+                let Some(card_id) = path.file_stem().and_then(|s| s.to_str()) else { continue };
+                let Ok(content) = fs::read_to_string(&path) else { continue };
+                let Some(file) = parse_session_file(&content) else { continue };
+                seen_cards.insert(card_id.to_string());
+
+                match self.sessions.get_mut(card_id) {
+                    // Same card relaunched while we still tracked the old run
+                    Some(session) if session.root != file.pid => {
+                        self.sessions.remove(card_id);
+                    }
+                    Some(session) => {
+                        session.exit_code = file.exit;
+                        session.ended = file.ended;
+                        continue;
+                    }
+                    None => {}
+                }
+
+                let mut procs = HashSet::new();
+                if let Some(start) = proc_start_time(file.pid) {
+                    procs.insert((file.pid, start));
+                }
+                // For sessions that ended while nobody was tracking them, the last
+                // write to the session file is the best guess for the end time
+                let last_seen = if procs.is_empty() {
+                    entry.metadata().ok()
+                        .and_then(|m| m.modified().ok())
+                        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                        .map_or(now, |d| d.as_secs())
+                } else {
+                    now
+                };
+                self.sessions.insert(card_id.to_string(), TrackedSession {
+                    started: file.started,
+                    root: file.pid,
+                    procs,
+                    last_seen,
+                    exit_code: file.exit,
+                    ended: file.ended,
+                });
+            }
+        }
+
+        // Session files removed behind our back are dropped without recording
+        self.sessions.retain(|card_id, _| seen_cards.contains(card_id));
+
+        let table = process_table();
+        let mut finished = Vec::new();
+        for (card_id, session) in self.sessions.iter_mut() {
+            session.procs = collect_tree(&table, &session.procs);
+            if !session.procs.is_empty() {
+                session.last_seen = now;
+                continue;
+            }
+            let ended = session.ended.unwrap_or(session.last_seen).max(session.started);
+            finished.push(FinishedSession {
+                card_id: card_id.clone(),
+                started: session.started,
+                ended,
+                exit_code: session.exit_code,
+            });
+        }
+
+        if !finished.is_empty() {
+            for session in &finished {
+                eprintln!(
+                    "Session finished: {} played {}s (exit code {:?})",
+                    session.card_id,
+                    session.ended - session.started,
+                    session.exit_code
+                );
+                self.sessions.remove(&session.card_id);
+                let _ = fs::remove_file(dir.join(format!("{}.session", session.card_id)));
+            }
+            let recorded = Library::update(|library| {
+                for session in &finished {
+                    library.record_session(&session.card_id, session.started, session.ended, session.exit_code);
+                }
+            });
+            if let Err(e) = recorded {
+                eprintln!("Failed to save library: {}", e);
+            }
+        }
+
+        finished
+    }
+
+    pub fn is_running(&self, card_id: &str) -> bool {
+        self.sessions.get(card_id).is_some_and(|s| !s.procs.is_empty())
+    }
+
+    /// Card ids with at least one live process.
+    pub fn running(&self) -> Vec<String> {
+        let mut cards: Vec<String> = self.sessions.iter()
+            .filter(|(_, s)| !s.procs.is_empty())
+            .map(|(card_id, _)| card_id.clone())
+            .collect();
+        cards.sort();
+        cards
+    }
+
+    /// Send SIGTERM to every process of a card's session.
+    pub fn terminate(&self, card_id: &str) -> bool {
+        let Some(session) = self.sessions.get(card_id) else { return false };
+        if session.procs.is_empty() {
+            return false;
+        }
+        let pids: Vec<String> = session.procs.iter().map(|(pid, _)| pid.to_string()).collect();
+        match Command::new("kill").arg("-TERM").args(&pids).status() {
+            Ok(status) => status.success(),
+            Err(e) => {
+                eprintln!("Failed to terminate session {}: {}", card_id, e);
+                false
+            }
+        }
+    }
+}
+
+/// Process-wide tracker shared by the helpers below.
+fn tracker() -> &'static Mutex<SessionTracker> {
+    static TRACKER: OnceLock<Mutex<SessionTracker>> = OnceLock::new();
+    TRACKER.get_or_init(|| Mutex::new(SessionTracker::new()))
+}
+
+/// Refresh the shared tracker. Call periodically so playtime is recorded promptly.
+pub fn refresh() -> Vec<FinishedSession> {
+    tracker().lock().map(|mut t| t.refresh()).unwrap_or_default()
+}
+
+pub fn is_running(card_id: &str) -> bool {
+    tracker().lock().map(|mut t| {
+        t.refresh();
+        t.is_running(card_id)
+    }).unwrap_or(false)
+}
+
+pub fn running_cards() -> Vec<String> {
+    tracker().lock().map(|mut t| {
+        t.refresh();
+        t.running()
+    }).unwrap_or_default()
+}
+
+pub fn terminate(card_id: &str) -> bool {
+    tracker().lock().map(|t| t.terminate(card_id)).unwrap_or(false)
+}
+
+pub fn sessions_dir() -> PathBuf {
+    dirs::runtime_dir()
+        .unwrap_or_else(env::temp_dir)
+        .join("jollypad")
+        .join("sessions")
+}
+
+/// Session file written by the launch wrapper for `card_id`.
+pub fn session_file(card_id: &str) -> PathBuf {
+    sessions_dir().join(format!("{}.session", card_id))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+#[derive(Debug, PartialEq)]
+struct SessionFile {
+    pid: u32,
+    started: u64,
+    exit: Option<i32>,
+    ended: Option<u64>,
+}
+
+fn parse_session_file(content: &str) -> Option<SessionFile> {
+    let mut pid = None;
+    let mut started = None;
+    let mut exit = None;
+    let mut ended = None;
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once('=') else { continue };
+        let value = value.trim();
+        match key.trim() {
+            "pid" => pid = value.parse().ok(),
+            "started" => started = value.parse().ok(),
+            "exit" => exit = value.parse().ok(),
+            "ended" => ended = value.parse().ok(),
+            _ => {}
+        }
+    }
+    Some(SessionFile { pid: pid?, started: started?, exit, ended })
+}
+
+/// Parent PID and start time (in clock ticks) from the contents of `/proc/<pid>/stat`.
+fn parse_stat(stat: &str) -> Option<(u32, u64)> {
+    // The command name may contain spaces and parentheses, so split after the last ')'
+    let rest = &stat[stat.rfind(')')? + 1..];
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    // fields[0] is the state (field 3), ppid is field 4, starttime is field 22
+    if fields.first() == Some(&"Z") {
+        return None;
+    }
+    let ppid = fields.get(1)?.parse().ok()?;
+    let start = fields.get(19)?.parse().ok()?;
+    Some((ppid, start))
+}
+
+fn proc_start_time(pid: u32) -> Option<u64> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    parse_stat(&stat).map(|(_, start)| start)
+}
+
+/// Live processes as `(pid, start time) -> ppid`.
+fn process_table() -> HashMap<ProcKey, u32> {
+    let mut table = HashMap::new();
+    let Ok(entries) = fs::read_dir("/proc") else { return table };
+    for entry in entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else { continue };
+        let Ok(stat) = fs::read_to_string(entry.path().join("stat")) else { continue };
+        if let Some((ppid, start)) = parse_stat(&stat) {
+            table.insert((pid, start), ppid);
+        }
+    }
+    table
+}
+
+/// Known processes that are still alive plus all their descendants.
+fn collect_tree(table: &HashMap<ProcKey, u32>, known: &HashSet<ProcKey>) -> HashSet<ProcKey> {
+    let mut tree: HashSet<ProcKey> = known.iter().filter(|p| table.contains_key(p)).copied().collect();
+    loop {
+        let pids: HashSet<u32> = tree.iter().map(|(pid, _)| *pid).collect();
+        let before = tree.len();
+        for (proc_key, ppid) in table {
+            if pids.contains(ppid) {
+                tree.insert(*proc_key);
+            }
+        }
+        if tree.len() == before {
+            return tree;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat_with_spaces_in_name() {
+        let stat = "4242 (Game (x64) Main) S 4100 4242 4100 0 -1 4194560 1 0 0 0 0 0 0 0 20 0 1 0 987654 0 0";
+        assert_eq!(parse_stat(stat), Some((4100, 987654)));
+        let zombie = "4242 (game) Z 4100 4242 4100 0 -1 4194560 1 0 0 0 0 0 0 0 20 0 1 0 987654 0 0";
+        assert_eq!(parse_stat(zombie), None);
+    }
+
+    #[test]
+    fn test_parse_session_file() {
+        let running = parse_session_file("pid=10\nstarted=100\n").unwrap();
+        assert_eq!(running, SessionFile { pid: 10, started: 100, exit: None, ended: None });
+        let done = parse_session_file("pid=10\nstarted=100\nexit=3\nended=160\n").unwrap();
+        assert_eq!(done.exit, Some(3));
+        assert_eq!(done.ended, Some(160));
+        assert!(parse_session_file("started=100\n").is_none());
+    }
+
+    #[test]
+    fn test_collect_tree_follows_orphans() {
+        let mut table = HashMap::new();
+        // Launcher (10) already exited; its child 11 was reparented to 1, 12 is a grandchild
+        table.insert((11, 5), 1);
+        table.insert((12, 6), 11);
+        table.insert((13, 7), 1);
+        let known: HashSet<ProcKey> = [(10, 4), (11, 5)].into_iter().collect();
+        let tree = collect_tree(&table, &known);
+        assert_eq!(tree, [(11, 5), (12, 6)].into_iter().collect());
+    }
+}
@@ -0,0 +1,369 @@
+//! Settings shared by the JollyPad apps, kept in
+//! `~/.config/jollypad/settings.toml`.
+//!
+//! The file carries a schema `version`. Older files are brought up to date by
+//! [`MIGRATIONS`] when they are read, and written in the current schema the
+//! next time anything is saved. Running apps notice saves with a [`Watcher`].
+
+use anyhow::{Context, Result};
+use catacomb_ipc::{IpcMessage, Orientation, OutputMode, WindowScale};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use toml::{Table, Value};
+
+/// Schema version written by this build.
+pub const VERSION: u32 = 1;
+
+/// `MIGRATIONS[n]` turns a version `n + 1` file into version `n + 2`. Files
+/// from before the schema was versioned are version 1.
+const MIGRATIONS: &[fn(&mut Table)] = &[];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Settings {
+    pub version: u32,
+    /// Id of the colour theme, see [`crate::theme`].
+    pub theme: String,
+    /// Catalog language code from [`crate::i18n::LANGUAGES`]; empty to follow the locale.
+    pub language: String,
+    /// Margins that keep the UI clear of the overscan of TVs.
+    pub safe_area: SafeArea,
+    pub accessibility: Accessibility,
+    pub display: Display,
+    pub keyboard: Keyboard,
+    /// Window scales of single apps, applied on top of `display.scale`.
+    pub scale_rules: Vec<ScaleRule>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: VERSION,
+            theme: "dark".to_string(),
+            language: String::new(),
+            safe_area: SafeArea::default(),
+            accessibility: Accessibility::default(),
+            display: Display::default(),
+            keyboard: Keyboard::default(),
+            scale_rules: Vec::new(),
+        }
+    }
+}
+
+/// Inset of each screen edge, in percent of the window size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SafeArea {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl SafeArea {
+    /// Largest inset per edge; more than that is a misconfigured TV, not overscan.
+    pub const MAX: f32 = 20.0;
+
+    /// The insets limited to `0..=MAX`, e.g. after editing the file by hand.
+    pub fn clamped(self) -> Self {
+        let clamp = |value: f32| if value.is_finite() { value.clamp(0.0, Self::MAX) } else { 0.0 };
+        Self { top: clamp(self.top), right: clamp(self.right), bottom: clamp(self.bottom), left: clamp(self.left) }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Accessibility {
+    /// Factor for all font sizes.
+    pub text_scale: f32,
+    /// Thick focus rings in a colour that stands out from any theme.
+    pub high_contrast_focus: bool,
+    /// No animated scrolling, zooming or fading.
+    pub reduce_motion: bool,
+    /// Bigger cards on home and in the nav bar.
+    pub large_cards: bool,
+}
+
+impl Default for Accessibility {
+    fn default() -> Self {
+        Self { text_scale: 1.0, high_contrast_focus: false, reduce_motion: false, large_cards: false }
+    }
+}
+
+impl Accessibility {
+    /// The options with the text scale limited to the 100% to 150% the settings offer.
+    pub fn clamped(self) -> Self {
+        let text_scale = if self.text_scale.is_finite() { self.text_scale.clamp(1.0, 1.5) } else { 1.0 };
+        Self { text_scale, ..self }
+    }
+}
+
+/// Output settings; unset ones are left to the compositor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Display {
+    pub mode: Option<DisplayMode>,
+    pub scale: Option<f64>,
+    /// Locked orientation; `None` rotates with the device.
+    pub orientation: Option<Orientation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisplayMode {
+    pub width: i32,
+    pub height: i32,
+    /// Refresh rate in mHz.
+    pub refresh: i32,
+}
+
+impl From<&OutputMode> for DisplayMode {
+    fn from(mode: &OutputMode) -> Self {
+        Self { width: mode.width, height: mode.height, refresh: mode.refresh }
+    }
+}
+
+/// XKB keymap names; empty ones keep the system default.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Keyboard {
+    pub model: String,
+    /// Comma-separated layouts.
+    pub layout: String,
+    /// Comma-separated variants, one per layout.
+    pub variant: String,
+    /// Comma-separated XKB options.
+    pub options: String,
+}
+
+impl Keyboard {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The message that sets this keymap; empty names fall back to the default.
+    pub fn message(&self) -> IpcMessage {
+        let name = |value: &str| (!value.is_empty()).then(|| value.to_string());
+        IpcMessage::KeyboardConfig {
+            model: name(&self.model),
+            layout: name(&self.layout),
+            variant: name(&self.variant),
+            options: name(&self.options),
+        }
+    }
+}
+
+/// The scale of the windows of one app, fixed or relative to the display scale.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ScaleRule {
+    pub app_id: String,
+    pub scale: WindowScale,
+}
+
+impl ScaleRule {
+    /// The message that scales the windows of exactly this app.
+    pub fn message(&self) -> IpcMessage {
+        IpcMessage::Scale { scale: self.scale, app_id: Some(app_id_pattern(&self.app_id)) }
+    }
+
+    /// The message that lets the windows of an app follow the display scale again.
+    pub fn reset_message(app_id: &str) -> IpcMessage {
+        IpcMessage::Scale { scale: WindowScale::Multiplicative(1.0), app_id: Some(app_id_pattern(app_id)) }
+    }
+}
+
+/// A regex matching only `app_id`, as the compositor takes app IDs as regexes.
+fn app_id_pattern(app_id: &str) -> String {
+    let mut pattern = String::from("^");
+    for c in app_id.chars() {
+        if "\\.+*?()|[]{}^$#&-~".contains(c) {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('$');
+    pattern
+}
+
+/// `~/.config/jollypad`, where settings and themes live.
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("jollypad"))
+}
+
+/// Bring a settings table to [`VERSION`].
+fn migrate(table: &mut Table) {
+    let version = table.get("version").and_then(Value::as_integer).unwrap_or(1);
+    let version = usize::try_from(version).unwrap_or(1).max(1);
+    if version > VERSION as usize {
+        // Read what we know; unknown keys are ignored
+        eprintln!("Settings are from a newer JollyPad (version {}), some may be lost", version);
+    }
+    for migration in MIGRATIONS.iter().skip(version - 1) {
+        migration(table);
+    }
+    table.insert("version".to_string(), Value::Integer(VERSION.into()));
+}
+
+impl Settings {
+    pub fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join("settings.toml"))
+    }
+
+    /// Read a settings file of any version.
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut table: Table = toml::from_str(content)?;
+        migrate(&mut table);
+        Ok(Value::Table(table).try_into()?)
+    }
+
+    /// Read the settings file. A missing file gives the defaults; one that
+    /// cannot be read or parsed is an error.
+    pub fn read() -> Result<Self> {
+        let path = Self::path().context("Could not find config directory")?;
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read settings {:?}", path)),
+        };
+        Self::parse(&content).with_context(|| format!("Failed to parse settings {:?}", path))
+    }
+
+    /// Load the settings for use, falling back to the defaults if they are unreadable.
+    pub fn load() -> Self {
+        Self::read().unwrap_or_else(|e| {
+            eprintln!("{:#}", e);
+            Self::default()
+        })
+    }
+
+    /// Load the settings, apply `change` and save them. All changes to the
+    /// settings file go through here, so a file that fails to parse is left
+    /// untouched rather than replaced by the defaults.
+    pub fn update(change: impl FnOnce(&mut Self)) -> Result<()> {
+        let mut settings = Self::read()?;
+        change(&mut settings);
+        settings.save()
+    }
+
+    /// Write the settings in the current schema. The file is replaced in one
+    /// step, so a [`Watcher`] never reads it half written.
+    fn save(&self) -> Result<()> {
+        let path = Self::path().context("Could not find config directory")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).context("Failed to create config directory")?;
+        }
+        let settings = Self { version: VERSION, ..self.clone() };
+        let content = toml::to_string_pretty(&settings).context("Failed to serialize settings")?;
+        let temp = path.with_extension("toml.tmp");
+        fs::write(&temp, content).with_context(|| format!("Failed to write {:?}", temp))?;
+        fs::rename(&temp, &path).with_context(|| format!("Failed to write {:?}", path))
+    }
+
+    /// The compositor messages that restore the saved display, app scale and
+    /// keyboard settings, for the launcher to send at startup.
+    pub fn compositor_messages(&self) -> Vec<IpcMessage> {
+        let mut messages = Vec::new();
+        if let Some(mode) = self.display.mode {
+            let mode = OutputMode { width: mode.width, height: mode.height, refresh: mode.refresh };
+            messages.push(IpcMessage::SetOutputMode { mode });
+        }
+        if let Some(scale) = self.display.scale {
+            messages.push(IpcMessage::Scale { scale: WindowScale::Fixed(scale), app_id: None });
+        }
+        messages.extend(self.scale_rules.iter().map(ScaleRule::message));
+        if let Some(orientation) = self.display.orientation {
+            messages.push(IpcMessage::Orientation { lock: Some(orientation), unlock: false });
+        }
+        if !self.keyboard.is_default() {
+            messages.push(self.keyboard.message());
+        }
+        messages
+    }
+}
+
+/// Notices when the settings file is saved, by this or another app.
+pub struct Watcher {
+    modified: Option<SystemTime>,
+}
+
+impl Default for Watcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Watcher {
+    /// Start watching from the settings as they are now.
+    pub fn new() -> Self {
+        Self { modified: Self::modified() }
+    }
+
+    fn modified() -> Option<SystemTime> {
+        fs::metadata(Settings::path()?).and_then(|meta| meta.modified()).ok()
+    }
+
+    /// The new settings if the file changed since the last call.
+    pub fn poll(&mut self) -> Option<Settings> {
+        let modified = Self::modified();
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(Settings::load())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_area() {
+        let settings = Settings::parse("version = 1\n[safe-area]\ntop = 2.5\nleft = 40.0\n").unwrap();
+        assert_eq!(settings.theme, "dark");
+        let area = settings.safe_area.clamped();
+        assert_eq!(area, SafeArea { top: 2.5, right: 0.0, bottom: 0.0, left: SafeArea::MAX });
+        assert_eq!(settings.accessibility, Accessibility::default());
+        assert_eq!(Accessibility { text_scale: 3.0, ..Accessibility::default() }.clamped().text_scale, 1.5);
+    }
+
+    #[test]
+    fn test_migrate_unversioned() {
+        let old = "theme = \"light\"\n[safe-area]\ntop = 3.0\n[accessibility]\ntext-scale = 1.25\nreduce-motion = true\n";
+        let settings = Settings::parse(old).unwrap();
+        assert_eq!(settings.version, VERSION);
+        assert_eq!(settings.theme, "light");
+        assert_eq!(settings.safe_area.top, 3.0);
+        assert_eq!(settings.accessibility.text_scale, 1.25);
+        assert!(settings.accessibility.reduce_motion);
+
+        // Saved files read back the same
+        let saved = toml::to_string_pretty(&settings).unwrap();
+        assert_eq!(Settings::parse(&saved).unwrap().accessibility, settings.accessibility);
+
+        let mut settings = Settings::default();
+        assert!(settings.compositor_messages().is_empty());
+        settings.display.scale = Some(1.5);
+        settings.display.orientation = Some(Orientation::Landscape);
+        settings.keyboard.layout = "us,de".to_string();
+        let messages = settings.compositor_messages();
+        assert_eq!(messages.len(), 3);
+        assert!(matches!(messages[2], IpcMessage::KeyboardConfig { model: None, layout: Some(ref l), .. } if l == "us,de"));
+    }
+
+    #[test]
+    fn test_scale_rules() {
+        let mut settings = Settings::default();
+        settings.scale_rules.push(ScaleRule { app_id: "org.gnome.Maps".to_string(), scale: WindowScale::Additive(0.5) });
+        let saved = toml::to_string_pretty(&settings).unwrap();
+        assert_eq!(Settings::parse(&saved).unwrap().scale_rules, settings.scale_rules);
+        let messages = settings.compositor_messages();
+        assert!(matches!(
+            messages[..],
+            [IpcMessage::Scale { scale: WindowScale::Additive(_), app_id: Some(ref id) }] if id == r"^org\.gnome\.Maps$"
+        ));
+    }
+}
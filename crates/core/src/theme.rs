@@ -0,0 +1,299 @@
+//! Colour themes for the JollyPad apps: the built-in dark, light and
+//! high-contrast themes plus user themes in `~/.config/jollypad/themes/*.toml`.
+//!
+//! A theme file sets any of the keys below; the rest come from the built-in
+//! theme named by `base` (dark when not given). The file name is the theme id.
+//!
+//! ```toml
+//! name = "Midnight"
+//! base = "dark"
+//! accent = "#8b5cf6"
+//! background = "#05050f"
+//! background-end = "#1e1b4b"   # optional, a vertical gradient
+//! card = "#ffffff0d"
+//! card-hover = "#ffffff1f"
+//! panel = "#1c1a33"
+//! text = "#f5f5ff"
+//! radius = 12
+//! padding = 24
+//! font = "Noto Sans"
+//! wallpaper = "~/Pictures/midnight.jpg"
+//! ```
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::settings::{self, Settings};
+
+/// An sRGB colour, written `#rgb`, `#rrggbb` or `#rrggbbaa` in theme files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        let hex = text.trim().strip_prefix('#')?;
+        if !hex.is_ascii() {
+            return None;
+        }
+        let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        let nibble = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|n| n * 17);
+        match hex.len() {
+            3 => Some(Self::rgba(nibble(0)?, nibble(1)?, nibble(2)?, 255)),
+            6 => Some(Self::rgba(byte(0)?, byte(2)?, byte(4)?, 255)),
+            8 => Some(Self::rgba(byte(0)?, byte(2)?, byte(4)?, byte(6)?)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)?;
+        if self.a != 255 {
+            write!(f, "{:02x}", self.a)?;
+        }
+        Ok(())
+    }
+}
+
+impl Color {
+    /// WCAG relative luminance, ignoring alpha.
+    pub fn luminance(self) -> f64 {
+        let channel = |c: u8| {
+            let c = f64::from(c) / 255.0;
+            if c <= 0.03928 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+        };
+        0.2126 * channel(self.r) + 0.7152 * channel(self.g) + 0.0722 * channel(self.b)
+    }
+
+    /// WCAG contrast ratio between two opaque colours, from 1 to 21.
+    pub fn contrast(self, other: Self) -> f64 {
+        let (a, b) = (self.luminance(), other.luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, String> {
+        Self::parse(&text).ok_or_else(|| format!("invalid colour {:?}", text))
+    }
+}
+
+impl From<Color> for String {
+    fn from(color: Color) -> String {
+        color.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Theme {
+    /// File name without `.toml`, or the built-in name.
+    #[serde(skip)]
+    pub id: String,
+    pub name: String,
+    /// Focus ring and highlights.
+    pub accent: Color,
+    pub background: Color,
+    /// Bottom colour of a vertical gradient from `background`.
+    #[serde(default)]
+    pub background_end: Option<Color>,
+    pub card: Color,
+    pub card_hover: Color,
+    /// Opaque background of dialogs, the file picker and the on-screen
+    /// keyboard; `text` is drawn on it.
+    pub panel: Color,
+    pub text: Color,
+    /// Corner radius of cards and panels, in logical pixels.
+    pub radius: f32,
+    pub padding: f32,
+    /// Font family; the system default when empty.
+    #[serde(default)]
+    pub font: String,
+    /// Picture drawn behind the home screen, `~/` is the home directory.
+    #[serde(default)]
+    pub wallpaper: Option<String>,
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            id: "dark".to_string(),
+            name: "Dark".to_string(),
+            accent: Color::rgba(0x3b, 0x82, 0xf6, 255),
+            background: Color::rgba(0x0b, 0x0b, 0x0b, 255),
+            background_end: Some(Color::rgba(0x1a, 0x1a, 0x2e, 255)),
+            card: Color::rgba(255, 255, 255, 13),
+            card_hover: Color::rgba(255, 255, 255, 26),
+            panel: Color::rgba(0x2b, 0x2b, 0x2b, 255),
+            text: Color::rgba(255, 255, 255, 255),
+            radius: 16.0,
+            padding: 24.0,
+            font: String::new(),
+            wallpaper: None,
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            id: "light".to_string(),
+            name: "Light".to_string(),
+            accent: Color::rgba(0x25, 0x63, 0xeb, 255),
+            background: Color::rgba(0xf4, 0xf4, 0xf6, 255),
+            background_end: Some(Color::rgba(0xdc, 0xdc, 0xe6, 255)),
+            card: Color::rgba(0, 0, 0, 13),
+            card_hover: Color::rgba(0, 0, 0, 31),
+            panel: Color::rgba(255, 255, 255, 255),
+            text: Color::rgba(0x1a, 0x1a, 0x1a, 255),
+            ..Self::dark()
+        }
+    }
+
+    /// Plain black, white text and a yellow focus ring.
+    pub fn high_contrast() -> Self {
+        Self {
+            id: "high-contrast".to_string(),
+            name: "High Contrast".to_string(),
+            accent: Color::rgba(0xff, 0xd4, 0x00, 255),
+            background: Color::rgba(0, 0, 0, 255),
+            background_end: None,
+            card: Color::rgba(255, 255, 255, 31),
+            card_hover: Color::rgba(255, 255, 255, 64),
+            panel: Color::rgba(0x14, 0x14, 0x14, 255),
+            text: Color::rgba(255, 255, 255, 255),
+            ..Self::dark()
+        }
+    }
+
+    pub fn builtin() -> Vec<Self> {
+        vec![Self::dark(), Self::light(), Self::high_contrast()]
+    }
+
+    /// Parse a theme file, filling missing keys from its `base` theme.
+    pub fn parse(id: &str, content: &str) -> Result<Self> {
+        let mut table: toml::Table = toml::from_str(content).context("Invalid TOML")?;
+        let base_id = match table.remove("base") {
+            Some(toml::Value::String(base)) => base,
+            Some(_) => anyhow::bail!("`base` must be a string"),
+            None => "dark".to_string(),
+        };
+        let base = Self::builtin()
+            .into_iter()
+            .find(|theme| theme.id == base_id)
+            .with_context(|| format!("Unknown base theme {:?}", base_id))?;
+        let mut merged = toml::Table::try_from(&base).context("Failed to serialize base theme")?;
+        // A theme based on a gradient one can turn the gradient off
+        if table.get("background").is_some() && !table.contains_key("background-end") {
+            merged.remove("background-end");
+        }
+        merged.extend(table);
+        let mut theme: Self = merged.try_into().context("Invalid theme")?;
+        theme.id = id.to_string();
+        if theme.name.is_empty() || theme.name == base.name {
+            theme.name = id.to_string();
+        }
+        Ok(theme)
+    }
+
+    /// The wallpaper path with `~/` expanded.
+    pub fn wallpaper_path(&self) -> Option<PathBuf> {
+        let path = self.wallpaper.as_deref().filter(|p| !p.is_empty())?;
+        match (path.strip_prefix("~/"), dirs::home_dir()) {
+            (Some(rest), Some(home)) => Some(home.join(rest)),
+            _ => Some(PathBuf::from(path)),
+        }
+    }
+}
+
+/// `~/.config/jollypad/themes`.
+pub fn themes_dir() -> Option<PathBuf> {
+    settings::config_dir().map(|dir| dir.join("themes"))
+}
+
+/// The built-in themes followed by the user's, sorted by name. A user theme
+/// with the id of a built-in one replaces it.
+pub fn load_themes() -> Vec<Theme> {
+    let mut themes = Theme::builtin();
+    let Some(entries) = themes_dir().and_then(|dir| fs::read_dir(dir).ok()) else { return themes };
+
+    let mut user: Vec<Theme> = Vec::new();
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+            continue;
+        }
+        let Some(id) = path.file_stem().and_then(|s| s.to_str()) else { continue };
+        let parsed = fs::read_to_string(&path).context("Failed to read theme").and_then(|c| Theme::parse(id, &c));
+        match parsed {
+            Ok(theme) => user.push(theme),
+            Err(e) => eprintln!("Failed to load theme {:?}: {:#}", path, e),
+        }
+    }
+    user.sort_by_key(|theme| theme.name.to_lowercase());
+    for theme in user {
+        match themes.iter_mut().find(|t| t.id == theme.id) {
+            Some(builtin) => *builtin = theme,
+            None => themes.push(theme),
+        }
+    }
+    themes
+}
+
+/// The theme chosen in the settings, or the dark theme.
+pub fn current() -> Theme {
+    let id = Settings::load().theme;
+    load_themes().into_iter().find(|theme| theme.id == id).unwrap_or_else(Theme::dark)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_theme() {
+        assert_eq!(Color::parse("#fff"), Some(Color::rgba(255, 255, 255, 255)));
+        assert_eq!(Color::parse("#3b82f680"), Some(Color::rgba(0x3b, 0x82, 0xf6, 0x80)));
+        assert_eq!(Color::parse("3b82f6"), None);
+        assert_eq!(Color::rgba(1, 2, 3, 4).to_string(), "#01020304");
+
+        let theme = Theme::parse("paper", "base = \"light\"\naccent = \"#ff0000\"\nradius = 4\n").unwrap();
+        assert_eq!(theme.id, "paper");
+        assert_eq!(theme.name, "paper");
+        assert_eq!(theme.accent, Color::rgba(255, 0, 0, 255));
+        assert_eq!(theme.text, Theme::light().text);
+        assert_eq!(theme.radius, 4.0);
+        assert_eq!(theme.background_end, Theme::light().background_end);
+
+        let flat = Theme::parse("flat", "name = \"Flat\"\nbackground = \"#202020\"\n").unwrap();
+        assert_eq!(flat.name, "Flat");
+        assert_eq!(flat.background_end, None);
+
+        assert!(Theme::parse("bad", "accent = \"blue\"").is_err());
+        assert!(Theme::parse("bad", "base = \"sepia\"").is_err());
+    }
+
+    #[test]
+    fn test_builtin_panel_contrast() {
+        assert_eq!(Color::rgba(0, 0, 0, 255).contrast(Color::rgba(255, 255, 255, 255)), 21.0);
+        // WCAG AA for normal text
+        for theme in Theme::builtin() {
+            assert_eq!(theme.panel.a, 255, "{}", theme.id);
+            let contrast = theme.text.contrast(theme.panel);
+            assert!(contrast >= 4.5, "{}: contrast {:.2}", theme.id, contrast);
+        }
+    }
+}
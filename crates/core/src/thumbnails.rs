@@ -0,0 +1,143 @@
+//! Downscaled copies of icons and artwork.
+//!
+//! Cards show images at a fixed size, so decoding a 512px PNG or rendering a
+//! large SVG on every start is wasted work. Thumbnails are cached as PNG under
+//! `~/.cache/jollypad/thumbnails/<size>/`, keyed by the source path, its size
+//! and mtime, so replacing an image produces a new thumbnail.
+
+use anyhow::{anyhow, Context, Result};
+use image::{imageops::FilterType, RgbaImage};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Decoded RGBA8 pixels, row by row.
+#[derive(Debug, Clone)]
+pub struct Thumbnail {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl From<RgbaImage> for Thumbnail {
+    fn from(image: RgbaImage) -> Self {
+        Thumbnail { width: image.width(), height: image.height(), pixels: image.into_raw() }
+    }
+}
+
+pub fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|d| d.join("jollypad").join("thumbnails"))
+}
+
+/// FNV-1a, stable across builds unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8], mut hash: u64) -> u64 {
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100_0000_01b3);
+    }
+    hash
+}
+
+fn cache_key(source: &Path) -> Result<String> {
+    let meta = fs::metadata(source).with_context(|| format!("Failed to read {:?}", source))?;
+    let modified = meta.modified().ok().and_then(|t| t.duration_since(UNIX_EPOCH).ok()).map_or(0, |d| d.as_nanos());
+    let mut hash = fnv1a(source.as_os_str().as_encoded_bytes(), 0xcbf2_9ce4_8422_2325);
+    hash = fnv1a(&meta.len().to_le_bytes(), hash);
+    hash = fnv1a(&modified.to_le_bytes(), hash);
+    Ok(format!("{:016x}", hash))
+}
+
+fn render_svg(source: &Path, size: u32) -> Result<RgbaImage> {
+    use resvg::{tiny_skia, usvg};
+    let data = fs::read(source).with_context(|| format!("Failed to read {:?}", source))?;
+    let tree = usvg::Tree::from_data(&data, &usvg::Options::default())?;
+    let svg = tree.size();
+    let scale = size as f32 / svg.width().max(svg.height());
+    let (width, height) = ((svg.width() * scale).round().max(1.0) as u32, (svg.height() * scale).round().max(1.0) as u32);
+    let mut pixmap = tiny_skia::Pixmap::new(width, height).ok_or_else(|| anyhow!("Invalid SVG size in {:?}", source))?;
+    resvg::render(&tree, tiny_skia::Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+    // tiny-skia keeps premultiplied alpha
+    let pixels = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|p| {
+            let c = p.demultiply();
+            [c.red(), c.green(), c.blue(), c.alpha()]
+        })
+        .collect();
+    RgbaImage::from_raw(width, height, pixels).ok_or_else(|| anyhow!("Failed to render {:?}", source))
+}
+
+/// Decode `source` so that it fits in `size`×`size`. Smaller images keep their size.
+fn decode(source: &Path, size: u32) -> Result<RgbaImage> {
+    let is_svg = source.extension().is_some_and(|e| e.eq_ignore_ascii_case("svg") || e.eq_ignore_ascii_case("svgz"));
+    if is_svg {
+        return render_svg(source, size);
+    }
+    let image = image::open(source).with_context(|| format!("Failed to decode {:?}", source))?;
+    if image.width() <= size && image.height() <= size {
+        return Ok(image.into_rgba8());
+    }
+    Ok(image.resize(size, size, FilterType::Triangle).into_rgba8())
+}
+
+fn load_with_cache(source: &Path, size: u32, cache: Option<&Path>) -> Result<Thumbnail> {
+    let Some(cache) = cache else {
+        return decode(source, size).map(Thumbnail::from);
+    };
+    let path = cache.join(size.to_string()).join(format!("{}.png", cache_key(source)?));
+    if let Ok(image) = image::open(&path) {
+        return Ok(image.into_rgba8().into());
+    }
+
+    let image = decode(source, size)?;
+    // A missing cache only costs time, so write errors are just logged
+    let written = (|| -> Result<()> {
+        fs::create_dir_all(path.parent().unwrap_or(cache))?;
+        let tmp = path.with_extension("png.tmp");
+        image.save_with_format(&tmp, image::ImageFormat::Png)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    })();
+    if let Err(e) = written {
+        eprintln!("Failed to cache thumbnail {:?}: {}", path, e);
+    }
+    Ok(image.into())
+}
+
+/// Thumbnail of the image at `source` fitting in `size`×`size`, from the cache
+/// when possible.
+pub fn load(source: &Path, size: u32) -> Result<Thumbnail> {
+    load_with_cache(source, size, cache_dir().as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thumbnails_are_scaled_and_cached() {
+        let dir = std::env::temp_dir().join(format!("jollypad-thumbs-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let cache = dir.join("cache");
+
+        let png = dir.join("cover.png");
+        RgbaImage::new(600, 300).save(&png).unwrap();
+        let thumb = load_with_cache(&png, 128, Some(&cache)).unwrap();
+        assert_eq!((thumb.width, thumb.height), (128, 64));
+        assert_eq!(fs::read_dir(cache.join("128")).unwrap().count(), 1);
+        let cached = load_with_cache(&png, 128, Some(&cache)).unwrap();
+        assert_eq!(cached.pixels, thumb.pixels);
+
+        let small = load_with_cache(&png, 1024, None).unwrap();
+        assert_eq!((small.width, small.height), (600, 300));
+
+        let svg = dir.join("icon.svg");
+        fs::write(&svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="16" height="32"><rect width="16" height="32" fill="red"/></svg>"#).unwrap();
+        let thumb = load_with_cache(&svg, 64, None).unwrap();
+        assert_eq!((thumb.width, thumb.height), (32, 64));
+        assert_eq!(&thumb.pixels[..4], &[255, 0, 0, 255]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
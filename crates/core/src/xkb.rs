@@ -0,0 +1,130 @@
+//! The keyboard models, layouts, variants and options the system knows, from
+//! the `evdev.lst` listing that xkeyboard-config installs next to its rules.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XkbItem {
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XkbVariant {
+    /// The layout the variant belongs to.
+    pub layout: String,
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Default)]
+pub struct XkbRules {
+    /// Models and layouts are sorted by description, for showing in a list.
+    pub models: Vec<XkbItem>,
+    pub layouts: Vec<XkbItem>,
+    pub variants: Vec<XkbVariant>,
+    /// Options in file order, which keeps each group together.
+    pub options: Vec<XkbItem>,
+}
+
+/// `$XKB_CONFIG_ROOT/rules/evdev.lst`, by default under `/usr/share/X11/xkb`.
+fn listing_path() -> PathBuf {
+    let root = env::var_os("XKB_CONFIG_ROOT").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("/usr/share/X11/xkb"));
+    root.join("rules").join("evdev.lst")
+}
+
+impl XkbRules {
+    /// Read the system listing; empty if there is none.
+    pub fn load() -> Self {
+        let path = listing_path();
+        match fs::read_to_string(&path) {
+            Ok(listing) => Self::parse(&listing),
+            Err(e) => {
+                eprintln!("Failed to read XKB rules {:?}: {}", path, e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Parse a `.lst` listing: `! model`, `! layout`, `! variant` and
+    /// `! option` sections of `name  description` lines.
+    pub fn parse(listing: &str) -> Self {
+        let mut rules = Self::default();
+        let mut section = "";
+        for line in listing.lines() {
+            if let Some(name) = line.strip_prefix('!') {
+                section = name.trim();
+                continue;
+            }
+            let line = line.trim();
+            let Some((name, description)) = line.split_once(char::is_whitespace) else { continue };
+            let item = XkbItem { name: name.to_string(), description: description.trim().to_string() };
+            match section {
+                "model" => rules.models.push(item),
+                "layout" => rules.layouts.push(item),
+                "variant" => {
+                    // `chr  us: Cherokee`
+                    if let Some((layout, description)) = item.description.split_once(": ") {
+                        rules.variants.push(XkbVariant {
+                            layout: layout.to_string(),
+                            name: item.name,
+                            description: description.to_string(),
+                        });
+                    }
+                }
+                // Names without a colon head a group of options
+                "option" if item.name.contains(':') => rules.options.push(item),
+                _ => {}
+            }
+        }
+        rules.models.sort_by(|a, b| a.description.cmp(&b.description));
+        rules.layouts.sort_by(|a, b| a.description.cmp(&b.description));
+        rules
+    }
+
+    pub fn variants_of<'a>(&'a self, layout: &'a str) -> impl Iterator<Item = &'a XkbVariant> + 'a {
+        self.variants.iter().filter(move |variant| variant.layout == layout)
+    }
+
+    /// The description of a model, layout or option name, or the name itself.
+    pub fn describe<'a>(items: &'a [XkbItem], name: &'a str) -> &'a str {
+        items.iter().find(|item| item.name == name).map_or(name, |item| item.description.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_listing() {
+        let listing = "\
+! model
+  pc105           Generic 105-key PC
+  chromebook      Chromebook
+
+! layout
+  us              English (US)
+  de              German
+
+! variant
+  chr             us: Cherokee
+  nodeadkeys      de: German (no dead keys)
+
+! option
+  grp                  Switching to another layout
+  grp:switch           Right Alt (while pressed)
+";
+        let rules = XkbRules::parse(listing);
+        assert_eq!(rules.models[0].name, "chromebook");
+        assert_eq!(rules.layouts.iter().map(|l| l.name.as_str()).collect::<Vec<_>>(), ["us", "de"]);
+        let german: Vec<_> = rules.variants_of("de").collect();
+        assert_eq!(german.len(), 1);
+        assert_eq!(german[0].description, "German (no dead keys)");
+        assert_eq!(rules.options, [XkbItem { name: "grp:switch".into(), description: "Right Alt (while pressed)".into() }]);
+        assert_eq!(XkbRules::describe(&rules.layouts, "de"), "German");
+        assert_eq!(XkbRules::describe(&rules.layouts, "xx"), "xx");
+    }
+}
@@ -0,0 +1,20 @@
+//! Pushes [`jollypad_core::settings::Accessibility`] into the `Accessibility`
+//! global of `accessibility.slint`.
+
+/// Set the `Accessibility` global of `$window` from a
+/// `jollypad_core::settings::Accessibility`:
+///
+/// ```ignore
+/// jollypad_ui_kit::apply_accessibility!(ui, &Settings::load().accessibility);
+/// ```
+#[macro_export]
+macro_rules! apply_accessibility {
+    ($window:expr, $accessibility:expr) => {{
+        let options = jollypad_core::settings::Accessibility::clamped(*$accessibility);
+        let global = slint::ComponentHandle::global::<Accessibility>(&$window);
+        global.set_text_scale(options.text_scale);
+        global.set_high_contrast_focus(options.high_contrast_focus);
+        global.set_reduce_motion(options.reduce_motion);
+        global.set_large_cards(options.large_cards);
+    }};
+}
@@ -0,0 +1,51 @@
+//! Language selection for an app: picks the catalog for `@tr()` in Slint
+//! and for [`jollypad_core::tr!`] in Rust from the same `.po` files.
+//!
+//! Apps build with `with_bundled_translations("../../translations")` and call
+//! [`init_translations!`](crate::init_translations) before creating windows.
+
+use jollypad_core::i18n::{self, Catalog};
+
+/// Switch to `language`, given the app's `(language, .po text)` catalogs.
+pub fn select(language: &str, catalogs: &[(&str, &str)]) {
+    let catalog = catalogs
+        .iter()
+        .find(|(code, _)| *code == language)
+        .map(|(_, po)| Catalog::parse(po))
+        .unwrap_or_default();
+    i18n::set_catalog(catalog);
+    if let Err(e) = slint::select_bundled_translation(language) {
+        eprintln!("Failed to select translation {}: {}", language, e);
+    }
+}
+
+/// Use the language from the settings or the locale, see [`i18n::language`].
+pub fn init(catalogs: &[(&str, &str)]) {
+    select(i18n::language(), catalogs);
+}
+
+/// The `(language, .po text)` catalogs of the calling package, embedded
+/// from `translations/<language>/LC_MESSAGES/<package>.po`.
+#[macro_export]
+macro_rules! translations {
+    () => {
+        &[
+            (
+                "en",
+                include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../../translations/en/LC_MESSAGES/", env!("CARGO_PKG_NAME"), ".po")),
+            ),
+            (
+                "zh_CN",
+                include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../../translations/zh_CN/LC_MESSAGES/", env!("CARGO_PKG_NAME"), ".po")),
+            ),
+        ]
+    };
+}
+
+/// Load the calling package's catalogs in the configured language.
+#[macro_export]
+macro_rules! init_translations {
+    () => {
+        $crate::i18n::init($crate::translations!())
+    };
+}
@@ -0,0 +1,130 @@
+//! Letter layouts for the on-screen keyboard.
+//!
+//! Each layout is a row of digits and three rows of letters. The numeric and
+//! symbol pages are the same everywhere and live in `osk.slint`.
+
+use std::env;
+use std::rc::Rc;
+
+use slint::{ModelRc, SharedString, VecModel};
+
+pub struct KeyboardLayout {
+    /// Short label shown on the layout key, e.g. "EN".
+    pub name: &'static str,
+    /// Languages (ISO 639-1) this layout is the default for.
+    pub languages: &'static [&'static str],
+    pub rows: &'static [&'static [&'static str]],
+}
+
+const DIGITS: &[&str] = &["1", "2", "3", "4", "5", "6", "7", "8", "9", "0"];
+
+pub const LAYOUTS: &[KeyboardLayout] = &[
+    KeyboardLayout {
+        name: "EN",
+        languages: &["en"],
+        rows: &[
+            DIGITS,
+            &["q", "w", "e", "r", "t", "y", "u", "i", "o", "p"],
+            &["a", "s", "d", "f", "g", "h", "j", "k", "l", "'"],
+            &["z", "x", "c", "v", "b", "n", "m", ",", ".", "-"],
+        ],
+    },
+    KeyboardLayout {
+        name: "DE",
+        languages: &["de"],
+        rows: &[
+            DIGITS,
+            &["q", "w", "e", "r", "t", "z", "u", "i", "o", "p", "ü"],
+            &["a", "s", "d", "f", "g", "h", "j", "k", "l", "ö", "ä"],
+            &["y", "x", "c", "v", "b", "n", "m", "ß", ",", ".", "-"],
+        ],
+    },
+    KeyboardLayout {
+        name: "FR",
+        languages: &["fr"],
+        rows: &[
+            DIGITS,
+            &["a", "z", "e", "r", "t", "y", "u", "i", "o", "p", "é"],
+            &["q", "s", "d", "f", "g", "h", "j", "k", "l", "m", "è"],
+            &["w", "x", "c", "v", "b", "n", "ç", "à", ",", ".", "-"],
+        ],
+    },
+    KeyboardLayout {
+        name: "ES",
+        languages: &["es"],
+        rows: &[
+            DIGITS,
+            &["q", "w", "e", "r", "t", "y", "u", "i", "o", "p", "á"],
+            &["a", "s", "d", "f", "g", "h", "j", "k", "l", "ñ", "é"],
+            &["z", "x", "c", "v", "b", "n", "m", "í", "ó", "ú", "-"],
+        ],
+    },
+    KeyboardLayout {
+        name: "RU",
+        languages: &["ru", "uk", "be"],
+        rows: &[
+            DIGITS,
+            &["й", "ц", "у", "к", "е", "н", "г", "ш", "щ", "з", "х"],
+            &["ф", "ы", "в", "а", "п", "р", "о", "л", "д", "ж", "э"],
+            &["я", "ч", "с", "м", "и", "т", "ь", "б", "ю", "ъ", "ё"],
+        ],
+    },
+];
+
+/// Index in [`LAYOUTS`] of the layout for a locale such as `de_AT.UTF-8`;
+/// English for anything without a layout of its own.
+pub fn layout_for_locale(locale: &str) -> usize {
+    let language = locale.split(['_', '.', '@', '-']).next().unwrap_or("").to_ascii_lowercase();
+    LAYOUTS.iter().position(|layout| layout.languages.contains(&language.as_str())).unwrap_or(0)
+}
+
+/// The layout for the session locale (`LC_ALL`, `LC_MESSAGES`, then `LANG`).
+pub fn system_layout() -> usize {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .map_or(0, |locale| layout_for_locale(&locale))
+}
+
+/// The key a letter turns into with shift; keys without a single-letter
+/// capital, like `ß`, stay as they are.
+fn shifted(key: &str) -> String {
+    let upper = key.to_uppercase();
+    if upper.chars().count() == key.chars().count() {
+        upper
+    } else {
+        key.to_string()
+    }
+}
+
+impl KeyboardLayout {
+    /// The rows as a Slint `[[string]]`, with or without shift.
+    pub fn rows_model(&self, shift: bool) -> ModelRc<ModelRc<SharedString>> {
+        let rows: Vec<ModelRc<SharedString>> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let keys: Vec<SharedString> =
+                    row.iter().map(|key| if shift { shifted(key).into() } else { (*key).into() }).collect();
+                ModelRc::from(Rc::new(VecModel::from(keys)))
+            })
+            .collect();
+        ModelRc::from(Rc::new(VecModel::from(rows)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_for_locale() {
+        assert_eq!(LAYOUTS[layout_for_locale("de_AT.UTF-8")].name, "DE");
+        assert_eq!(LAYOUTS[layout_for_locale("uk_UA")].name, "RU");
+        assert_eq!(LAYOUTS[layout_for_locale("zh_CN.UTF-8")].name, "EN");
+        assert_eq!(LAYOUTS[layout_for_locale("C")].name, "EN");
+        assert_eq!(shifted("ß"), "ß");
+        assert_eq!(shifted("ё"), "Ё");
+    }
+}
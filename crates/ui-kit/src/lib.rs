@@ -1 +1,9 @@
 slint::include_modules!();
+
+pub mod accessibility;
+pub mod i18n;
+pub mod keyboard;
+pub mod safe_area;
+pub mod settings;
+pub mod text_input;
+pub mod theme;
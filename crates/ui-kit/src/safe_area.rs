@@ -0,0 +1,20 @@
+//! TV overscan compensation: pushes [`jollypad_core::settings::SafeArea`]
+//! into the `SafeAreaInsets` global of `safearea.slint`.
+
+/// Set the `SafeAreaInsets` global of `$window` from a
+/// `jollypad_core::settings::SafeArea`:
+///
+/// ```ignore
+/// jollypad_ui_kit::apply_safe_area!(ui, &Settings::load().safe_area);
+/// ```
+#[macro_export]
+macro_rules! apply_safe_area {
+    ($window:expr, $safe_area:expr) => {{
+        let area = jollypad_core::settings::SafeArea::clamped(*$safe_area);
+        let global = slint::ComponentHandle::global::<SafeAreaInsets>(&$window);
+        global.set_top(area.top);
+        global.set_right(area.right);
+        global.set_bottom(area.bottom);
+        global.set_left(area.left);
+    }};
+}
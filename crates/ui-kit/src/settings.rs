@@ -0,0 +1,54 @@
+//! Keeps a window in step with settings saved by jolly-settings or any other
+//! app, see [`jollypad_core::settings::Watcher`].
+
+use std::time::Duration;
+
+use jollypad_core::settings::{Settings, Watcher};
+use slint::{Timer, TimerMode};
+
+/// Call `on_change` with the new settings each time they are saved, checking
+/// once a second. The watch lasts as long as the returned timer.
+pub fn watch(mut on_change: impl FnMut(&Settings) + 'static) -> Timer {
+    let mut watcher = Watcher::new();
+    let timer = Timer::default();
+    timer.start(TimerMode::Repeated, Duration::from_secs(1), move || {
+        if let Some(settings) = watcher.poll() {
+            on_change(&settings);
+        }
+    });
+    timer
+}
+
+/// Watch the settings and re-apply the theme, safe area, accessibility
+/// options and language to `$window` when they change. Strings built in Rust
+/// with `tr!()` can be rebuilt in an optional `$on_language` callback, which
+/// runs after the new catalog is loaded. Evaluates to the timer to keep alive:
+///
+/// ```ignore
+/// let _settings_watch = jollypad_ui_kit::follow_settings!(ui);
+/// let _settings_watch = jollypad_ui_kit::follow_settings!(ui, move || rebuild_labels());
+/// ```
+#[macro_export]
+macro_rules! follow_settings {
+    ($window:expr) => {
+        $crate::follow_settings!($window, || {})
+    };
+    ($window:expr, $on_language:expr) => {{
+        let window = slint::ComponentHandle::as_weak(&$window);
+        let mut language = jollypad_core::settings::Settings::load().language;
+        let mut on_language = $on_language;
+        $crate::settings::watch(move |settings| {
+            let Some(window) = window.upgrade() else { return };
+            $crate::apply_theme!(window, &jollypad_core::theme::current());
+            $crate::apply_safe_area!(window, &settings.safe_area);
+            $crate::apply_accessibility!(window, &settings.accessibility);
+            // Strings from `@tr()` follow right away, the ones built in Rust
+            // once `on_language` rebuilds them
+            if settings.language != language {
+                language = settings.language.clone();
+                $crate::init_translations!();
+                on_language();
+            }
+        })
+    }};
+}
@@ -0,0 +1,122 @@
+//! Text entry for any JollyPad window: [`request_text`] opens a full screen
+//! on-screen keyboard and hands the result to a callback.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use slint::{CloseRequestResponse, ComponentHandle, ModelRc, SharedString, Timer, VecModel};
+
+use crate::keyboard::{self, LAYOUTS};
+use crate::{Accessibility, OskLayout, SafeAreaInsets, TextInputWindow, Theme};
+
+/// What to ask the user for.
+#[derive(Debug, Clone, Default)]
+pub struct TextRequest {
+    /// Shown above the text, e.g. "Card name".
+    pub prompt: String,
+    /// Text to start editing from.
+    pub initial: String,
+    /// Open on the number page, for numeric fields.
+    pub numeric: bool,
+    /// Longest accepted text in characters; unlimited when `None`.
+    pub max_chars: Option<usize>,
+}
+
+type Finish = Rc<dyn Fn(Option<String>)>;
+type OnDone = Box<dyn FnOnce(Option<String>)>;
+
+thread_local! {
+    // The last entry window and how to cancel it. It stays here after being
+    // answered, hidden, until the next request replaces it.
+    static ACTIVE: RefCell<Option<(TextInputWindow, Finish)>> = const { RefCell::new(None) };
+}
+
+type Rows = ModelRc<ModelRc<SharedString>>;
+
+/// Every keyboard layout, for the `layouts` property of an `OnScreenKeyboard`.
+/// Each app compiles `types.slint` into its own `OskLayout`, so `layout`
+/// builds one from a name and the unshifted and shifted rows:
+///
+/// ```ignore
+/// layouts_model(|name, letters, shifted| OskLayout { name, letters, shifted })
+/// ```
+pub fn layouts_model<L: Clone + 'static>(layout: impl Fn(SharedString, Rows, Rows) -> L) -> ModelRc<L> {
+    let layouts: Vec<L> =
+        LAYOUTS.iter().map(|l| layout(l.name.into(), l.rows_model(false), l.rows_model(true))).collect();
+    ModelRc::from(Rc::new(VecModel::from(layouts)))
+}
+
+/// Ask for a line of text. `on_done` gets the text once the user accepts it,
+/// or `None` if they back out. Only one request is open at a time; a new one
+/// cancels the previous. Must be called on the Slint event loop thread.
+pub fn request_text(
+    request: TextRequest,
+    on_done: impl FnOnce(Option<String>) + 'static,
+) -> Result<(), slint::PlatformError> {
+    if let Some((_, cancel)) = ACTIVE.with(|active| active.borrow_mut().take()) {
+        cancel(None);
+    }
+
+    let window = TextInputWindow::new()?;
+    crate::apply_theme!(window, &jollypad_core::theme::current());
+    let settings = jollypad_core::settings::Settings::load();
+    crate::apply_safe_area!(window, &settings.safe_area);
+    crate::apply_accessibility!(window, &settings.accessibility);
+    window.set_layouts(layouts_model(|name, letters, shifted| OskLayout { name, letters, shifted }));
+    window.set_layout_index(keyboard::system_layout() as i32);
+    window.set_page(if request.numeric { 1 } else { 0 });
+    window.set_prompt(request.prompt.into());
+    window.set_text(request.initial.as_str().into());
+
+    let text = Rc::new(RefCell::new(request.initial));
+    let on_done: RefCell<Option<OnDone>> = RefCell::new(Some(Box::new(on_done)));
+    let finish: Finish = {
+        let window = window.as_weak();
+        Rc::new(move |result| {
+            if let Some(window) = window.upgrade() {
+                let _ = window.hide();
+            }
+            // Answer after the key handler returns, so `on_done` may open another request
+            if let Some(on_done) = on_done.borrow_mut().take() {
+                Timer::single_shot(Duration::ZERO, move || on_done(result));
+            }
+        })
+    };
+
+    let max_chars = request.max_chars;
+    let text_typed = text.clone();
+    let window_typed = window.as_weak();
+    window.on_typed(move |typed| {
+        let mut text = text_typed.borrow_mut();
+        if max_chars.is_some_and(|max| text.chars().count() + typed.chars().count() > max) {
+            return;
+        }
+        text.push_str(&typed);
+        if let Some(window) = window_typed.upgrade() {
+            window.set_text(text.as_str().into());
+        }
+    });
+    let text_backspace = text.clone();
+    let window_backspace = window.as_weak();
+    window.on_backspace(move || {
+        let mut text = text_backspace.borrow_mut();
+        text.pop();
+        if let Some(window) = window_backspace.upgrade() {
+            window.set_text(text.as_str().into());
+        }
+    });
+    let finish_accepted = finish.clone();
+    window.on_accepted(move || finish_accepted(Some(text.borrow().clone())));
+    let finish_cancelled = finish.clone();
+    window.on_cancelled(move || finish_cancelled(None));
+    let finish_closed = finish.clone();
+    window.window().on_close_requested(move || {
+        finish_closed(None);
+        CloseRequestResponse::HideWindow
+    });
+
+    window.show()?;
+    ACTIVE.with(|active| *active.borrow_mut() = Some((window, finish)));
+    Ok(())
+}
@@ -0,0 +1,59 @@
+//! Runtime theming: turns a [`jollypad_core::theme::Theme`] into Slint values
+//! for the `Theme` global of `styles.slint`.
+//!
+//! Every app compiles `styles.slint` into its own `Theme` type, so the values
+//! are pushed with the [`apply_theme!`](crate::apply_theme) macro, which
+//! resolves `Theme` in the calling crate.
+
+use jollypad_core::theme::{Color, Theme};
+use slint::Image;
+
+pub fn color(color: Color) -> slint::Color {
+    slint::Color::from_argb_u8(color.a, color.r, color.g, color.b)
+}
+
+/// The top and bottom colours of the background gradient, the same colour
+/// twice for a plain background.
+pub fn background(theme: &Theme) -> (slint::Color, slint::Color) {
+    let start = color(theme.background);
+    (start, theme.background_end.map(color).unwrap_or(start))
+}
+
+/// The wallpaper picture, if the theme has one and it loads.
+pub fn wallpaper(theme: &Theme) -> Option<Image> {
+    let path = theme.wallpaper_path()?;
+    match Image::load_from_path(&path) {
+        Ok(image) => Some(image),
+        Err(e) => {
+            eprintln!("Failed to load wallpaper {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Set the `Theme` global of `$window` from a `jollypad_core::theme::Theme`:
+///
+/// ```ignore
+/// jollypad_ui_kit::apply_theme!(ui, &jollypad_core::theme::current());
+/// ```
+#[macro_export]
+macro_rules! apply_theme {
+    ($window:expr, $theme:expr) => {{
+        let theme: &jollypad_core::theme::Theme = $theme;
+        let global = slint::ComponentHandle::global::<Theme>(&$window);
+        let (background_start, background_end) = $crate::theme::background(theme);
+        global.set_background_start(background_start);
+        global.set_background_end(background_end);
+        global.set_card_bg($crate::theme::color(theme.card));
+        global.set_card_hover($crate::theme::color(theme.card_hover));
+        global.set_panel_bg($crate::theme::color(theme.panel));
+        global.set_focus_color($crate::theme::color(theme.accent));
+        global.set_text_color($crate::theme::color(theme.text));
+        global.set_padding(theme.padding);
+        global.set_border_radius(theme.radius);
+        global.set_font_family(theme.font.as_str().into());
+        let wallpaper = $crate::theme::wallpaper(theme);
+        global.set_has_wallpaper(wallpaper.is_some());
+        global.set_wallpaper(wallpaper.unwrap_or_default());
+    }};
+}